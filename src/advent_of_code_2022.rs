@@ -1,9 +1,5 @@
-use crate::aoc_result::AOCResult;
+use crate::solver::Puzzle;
 
-mod day4;
-pub fn advent_of_code_2022(day: u8) -> AOCResult {
-    match day {
-        4 => day4::solve(),
-        _ => todo!(),
-    }
-}
+pub mod day4;
+
+pub const SOLVERS: &[&dyn Puzzle] = &[&day4::Day4];
@@ -1,65 +1,91 @@
-use crate::{aoc_result::AOCResult, read_file::read_file};
+use crate::{
+    aoc_error::AocError,
+    interval::Interval,
+    solver::{PartOutput, Solver},
+};
 
-type Range = (u32, u32);
+type Range = Interval<u64>;
 type Line = (Range, Range);
 
-pub fn solve() -> AOCResult {
-    let content = read_file(2022, 4).expect("File input/2022/04.txt to exist");
-    let parsed = parse::lines(&content).expect("expected parsing to be successful");
+pub struct Day4;
 
-    (silver(&parsed), gold(&parsed)).into()
+impl Solver for Day4 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 4;
+
+    type Input = Vec<Line>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse::lines(input)
+    }
+
+    fn silver(input: &Self::Input) -> impl PartOutput {
+        silver(input)
+    }
+
+    fn gold(input: &Self::Input) -> impl PartOutput {
+        gold(input)
+    }
 }
 
 fn silver(lines: &[Line]) -> usize {
     lines.iter().filter(|line| silver_line(line)).count()
 }
 
-fn silver_line(line: &Line) -> bool {
-    let ((left_start, left_end), (right_start, right_end)) = line;
-    (left_start <= right_start && left_end >= right_end)
-        || (left_start >= right_start && left_end <= right_end)
+fn silver_line((left, right): &Line) -> bool {
+    left.contains_interval(right) || right.contains_interval(left)
 }
 
 fn gold(lines: &[Line]) -> usize {
     lines.iter().filter(|line| gold_line(line)).count()
 }
 
-fn gold_line(line: &Line) -> bool {
-    let ((left_start, left_end), (right_start, right_end)) = line;
-    (left_start >= right_start && left_start <= right_end)
-        || (left_end >= right_start && left_end <= right_end)
-        || (right_start >= left_start && right_start <= left_end)
-        || (right_end >= left_start && right_end <= left_end)
+fn gold_line((left, right): &Line) -> bool {
+    left.overlaps(right)
 }
 
 mod parse {
     use nom::{
         self,
         bytes::complete::tag,
-        character::complete::{newline, u32 as nom_u32},
+        character::complete::{newline, u64 as nom_u64},
+        combinator::map_opt,
+        error::context,
         multi::separated_list1,
         sequence::separated_pair,
-        IResult,
     };
 
-    use super::{Line, Range};
+    use super::{Interval, Line, Range};
+    use crate::{
+        aoc_error::AocError,
+        diagnostic::{self, ParseResult},
+    };
 
-    fn elf_section(input: &str) -> IResult<&str, Range> {
-        separated_pair(nom_u32, tag("-"), nom_u32)(input)
+    fn elf_section(input: &str) -> ParseResult<'_, Range> {
+        context(
+            "section range",
+            map_opt(
+                separated_pair(nom_u64, tag("-"), nom_u64),
+                |(first, last)| Interval::from_inclusive(first, last),
+            ),
+        )(input)
     }
 
-    fn line(input: &str) -> IResult<&str, Line> {
-        separated_pair(elf_section, tag(","), elf_section)(input)
+    fn line(input: &str) -> ParseResult<'_, Line> {
+        context(
+            "pair of section ranges",
+            separated_pair(elf_section, tag(","), elf_section),
+        )(input)
     }
 
-    pub fn lines(input: &str) -> Result<Vec<Line>, nom::Err<nom::error::Error<&str>>> {
-        separated_list1(newline, line)(input).map(|(_, lines)| lines)
+    pub fn lines(input: &str) -> Result<Vec<Line>, AocError> {
+        diagnostic::parse_with(input, separated_list1(newline, line))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{gold, parse, silver, silver_line, Line};
+    use super::{gold, parse, silver, silver_line, Interval, Line, Range};
 
     const INPUT: &str = "2-4,6-8
 2-3,4-5
@@ -71,22 +97,23 @@ mod tests {
     #[test]
     fn test_parse() {
         let lines = parse::lines(INPUT).unwrap();
+        let range = |first, last| -> Range { Interval::from_inclusive(first, last).unwrap() };
         assert_eq!(
             lines,
             vec![
-                ((2, 4), (6, 8)),
-                ((2, 3), (4, 5)),
-                ((5, 7), (7, 9)),
-                ((2, 8), (3, 7)),
-                ((6, 6), (4, 6)),
-                ((2, 6), (4, 8)),
+                (range(2, 4), range(6, 8)),
+                (range(2, 3), range(4, 5)),
+                (range(5, 7), range(7, 9)),
+                (range(2, 8), range(3, 7)),
+                (range(6, 6), range(4, 6)),
+                (range(2, 6), range(4, 8)),
             ]
         );
     }
 
     #[test]
     fn test_silver_line() {
-        const LINE: Line = ((6, 6), (3, 6));
+        const LINE: Line = (Interval::new(6, 7), Interval::new(3, 7));
         assert!(silver_line(&LINE));
     }
 
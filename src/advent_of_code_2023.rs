@@ -1,34 +1,31 @@
-use crate::aoc_result::AOCResult;
+use crate::solver::Puzzle;
 
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
 
-pub fn advent_of_code_2023(day: u8) -> AOCResult {
-    match day {
-        1 => day1::solve(),
-        2 => day2::solve(),
-        3 => day3::solve(),
-        4 => day4::solve(),
-        5 => day5::solve(),
-        6 => day6::solve(),
-        7 => day7::solve(),
-        8 => day8::solve(),
-        9 => day9::solve(),
-        10 => day10::solve(),
-        11 => day11::solve(),
-        12 => day12::solve(),
-        13 => day13::solve(),
-        _ => todo!(),
-    }
-}
+pub const SOLVERS: &[&dyn Puzzle] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+];
@@ -1,17 +1,34 @@
-use crate::aoc_result::AOCResult;
-use crate::read_file::read_file;
+use crate::{
+    aoc_error::AocError,
+    solver::{PartOutput, Solver},
+};
 
-pub fn solve() -> AOCResult {
-    let content = read_file(2023, 1).expect("File input/2023/01.txt to exist");
+pub struct Day1;
 
-    let silver = parse::lines(&content, &parse::silver_parser);
-    let gold = parse::lines(&content, &parse::gold_parser);
+impl Solver for Day1 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 1;
 
-    (sum(silver), sum(gold)).into()
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        Ok(input.to_owned())
+    }
+
+    fn silver(input: &Self::Input) -> impl PartOutput {
+        sum(parse::lines(input, &parse::silver_parser))
+    }
+
+    fn gold(input: &Self::Input) -> impl PartOutput {
+        sum(parse::lines(input, &parse::gold_parser))
+    }
 }
 
-fn sum(lines: impl Iterator<Item = LineInfo>) -> u32 {
-    lines.map(|l| l.to_result()).sum()
+fn sum(mut lines: impl Iterator<Item = Result<LineInfo, AocError>>) -> Result<u64, AocError> {
+    lines.try_fold(0_u64, |acc, line| {
+        acc.checked_add(u64::from(line?.to_result()))
+            .ok_or_else(|| AocError::overflow("the calibration sum"))
+    })
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -41,17 +58,22 @@ mod parse {
         IResult,
     };
 
-    use super::LineInfo;
+    use super::{AocError, LineInfo};
 
-    pub fn lines<'a, F>(input: &'a str, parser: &'a F) -> impl Iterator<Item = LineInfo> + 'a
+    pub fn lines<'a, F>(
+        input: &'a str,
+        parser: &'a F,
+    ) -> impl Iterator<Item = Result<LineInfo, AocError>> + 'a
     where
         F: Fn(&str) -> IResult<&str, u32> + 'a,
     {
         let line_parser = line(parser);
-        input.lines().map(line_parser)
+        input
+            .lines()
+            .map(move |l| line_parser(l).ok_or_else(|| AocError::parse(input, l, "no digit")))
     }
 
-    fn line<'a, F>(parser: &'a F) -> impl Fn(&str) -> LineInfo + 'a
+    fn line<'a, F>(parser: &'a F) -> impl Fn(&str) -> Option<LineInfo> + 'a
     where
         F: Fn(&str) -> IResult<&str, u32> + 'a,
     {
@@ -59,14 +81,12 @@ mod parse {
             let mut previous_input = input;
             let mut find_first = || loop {
                 if let Ok((_, found)) = parser(previous_input) {
-                    return LineInfo::from(found, found);
+                    return Some(LineInfo::from(found, found));
                 };
-                // line should always contain at least one number
-                // hence the unwrap
-                previous_input = take_one(previous_input).unwrap();
+                previous_input = take_one(previous_input)?;
             };
 
-            let mut result = find_first();
+            let mut result = find_first()?;
             while let Some(next_value) = take_one(previous_input) {
                 if let Ok((_, value)) = parser(previous_input) {
                     result = result.progressive_parse(value);
@@ -75,7 +95,7 @@ mod parse {
                 previous_input = next_value;
             }
 
-            result
+            Some(result)
         }
     }
 
@@ -147,16 +167,22 @@ zoneight234
 
     #[test]
     fn test_parse() {
-        let lines: Vec<LineInfo> = parse::lines(SILVER_INPUT, &parse::silver_parser).collect();
+        let lines: Vec<LineInfo> = parse::lines(SILVER_INPUT, &parse::silver_parser)
+            .map(Result::unwrap)
+            .collect();
         assert_eq!(lines, SILVER_INPUT_EXPECTED);
     }
 
     #[test]
     fn test_gold_parse() {
-        let lines = parse::lines(SILVER_INPUT, &parse::gold_parser).collect::<Vec<LineInfo>>();
+        let lines = parse::lines(SILVER_INPUT, &parse::gold_parser)
+            .map(Result::unwrap)
+            .collect::<Vec<LineInfo>>();
         assert_eq!(lines, SILVER_INPUT_EXPECTED);
 
-        let lines: Vec<LineInfo> = parse::lines(GOLD_INPUT, &parse::gold_parser).collect();
+        let lines: Vec<LineInfo> = parse::lines(GOLD_INPUT, &parse::gold_parser)
+            .map(Result::unwrap)
+            .collect();
 
         assert_eq!(lines, GOLD_INPUT_EXPECTED);
     }
@@ -164,8 +190,14 @@ zoneight234
     #[test]
     fn test_gold() {
         let lines = parse::lines(SILVER_INPUT, &parse::gold_parser);
-        assert_eq!(sum(lines), 142);
+        assert_eq!(sum(lines).unwrap(), 142);
         let lines = parse::lines(GOLD_INPUT, &parse::gold_parser);
-        assert_eq!(sum(lines), 281);
+        assert_eq!(sum(lines).unwrap(), 281);
+    }
+
+    #[test]
+    fn test_line_without_digit() {
+        let lines = parse::lines("1abc2\nabc", &parse::silver_parser);
+        assert!(sum(lines).is_err());
     }
 }
@@ -1,16 +1,42 @@
-use std::{collections::HashMap, iter, ops::RangeInclusive};
+use crate::{
+    aoc_error::AocError,
+    dot::Dot,
+    geometry::{Direction, Point},
+    grid::Grid,
+    solver::{DumpOptions, PartOutput, Solver},
+};
 
-use crate::{aoc_result::AOCResult, read_file::read_file};
+pub struct Day10;
 
-pub fn solve() -> AOCResult {
-    let input = read_file(2023, 10).expect("File input/2023/10.txt");
-    let parsed = parse::parse(&input);
-    let parsed2 = parse::parse(&input);
-    (silver(parsed), gold(parsed2)).into()
+impl Solver for Day10 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 10;
+
+    type Input = (Map, Point);
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse::parse(input)
+    }
+
+    fn silver(input: &Self::Input) -> impl PartOutput {
+        silver(input)
+    }
+
+    fn gold(input: &Self::Input) -> impl PartOutput {
+        gold(input)
+    }
+
+    fn dump(input: &Self::Input, _options: DumpOptions) -> Result<Option<String>, AocError> {
+        Ok(Some(render(&classify(input)?, input.1)))
+    }
+
+    fn dot(input: &Self::Input) -> Result<Option<Dot>, AocError> {
+        loop_dot(input).map(Some)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Copy, Clone)]
-enum Pipe {
+pub enum Pipe {
     DownLeft,
     DownRight,
     LeftRight,
@@ -19,239 +45,276 @@ enum Pipe {
     UpRight,
 }
 
-impl TryFrom<u8> for Pipe {
+impl TryFrom<char> for Pipe {
     type Error = &'static str;
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            b'7' => Ok(Self::DownLeft),
-            b'.' => Err("No pipe here"),
-            b'F' => Ok(Self::DownRight),
-            b'J' => Ok(Self::UpLeft),
-            b'L' => Ok(Self::UpRight),
-            b'-' => Ok(Self::LeftRight),
-            b'|' => Ok(Self::UpDown),
+            '7' => Ok(Self::DownLeft),
+            '.' => Err("No pipe here"),
+            'F' => Ok(Self::DownRight),
+            'J' => Ok(Self::UpLeft),
+            'L' => Ok(Self::UpRight),
+            '-' => Ok(Self::LeftRight),
+            '|' => Ok(Self::UpDown),
             _ => Err("Invalid character encountered"),
         }
     }
 }
 
 impl Pipe {
-    const fn offsets(self) -> ((i8, i8), (i8, i8)) {
-        const DOWN: (i8, i8) = (0, 1);
-        const UP: (i8, i8) = (0, -1);
-        const LEFT: (i8, i8) = (-1, 0);
-        const RIGHT: (i8, i8) = (1, 0);
-        match self {
-            Self::DownLeft => (DOWN, LEFT),
-            Self::DownRight => (DOWN, RIGHT),
-            Self::LeftRight => (LEFT, RIGHT),
-            Self::UpDown => (UP, DOWN),
-            Self::UpLeft => (UP, LEFT),
-            Self::UpRight => (UP, RIGHT),
-        }
-    }
-}
-
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd)]
-struct Point {
-    x: u8,
-    y: u8,
-}
-
-impl Point {
-    const fn new((x, y): (u8, u8)) -> Self {
-        Self { x, y }
-    }
-
-    fn list_neighbors(&self) -> impl Iterator<Item = Self> + '_ {
-        let (x_range, y_range) = self.get_ranges();
-
-        x_range
+    const ALL: [Self; 6] = [
+        Self::DownLeft,
+        Self::DownRight,
+        Self::LeftRight,
+        Self::UpDown,
+        Self::UpLeft,
+        Self::UpRight,
+    ];
+
+    /// The pipe joining both directions, if they differ.
+    fn connecting(a: Direction, b: Direction) -> Option<Self> {
+        Self::ALL
             .into_iter()
-            .map(move |x| iter::repeat(x).zip(y_range.clone()).map(Self::new))
-            .flatten()
-            .filter(|point| *self != *point)
+            .find(|pipe| pipe.connects(a) && pipe.connects(b) && a != b)
     }
 
-    fn list_neighbors_pointing_to_self<'a>(
-        &'a self,
-        map: &'a HashMap<Point, Pipe>,
-    ) -> impl Iterator<Item = Point> + 'a {
-        self.list_neighbors()
-            .map(|neighbors| map.get_key_value(&neighbors))
-            .filter(Option::is_some)
-            .map(Option::unwrap)
-            .filter(|(point, pipe)| {
-                let offsets = pipe.offsets();
-
-                point.from_offset(offsets.0) == Some(*self)
-                    || point.from_offset(offsets.1) == Some(*self)
-            })
-            .map(|(point, _)| *point)
+    fn connects(self, direction: Direction) -> bool {
+        let (a, b) = self.directions();
+        a == direction || b == direction
     }
 
-    const fn get_ranges(self) -> (RangeInclusive<u8>, RangeInclusive<u8>) {
-        let x_range = self.x.saturating_sub(1)..=self.x.saturating_add(1);
-        let y_range = self.y.saturating_sub(1)..=self.y.saturating_add(1);
-
-        (x_range, y_range)
+    const fn box_drawing(self) -> char {
+        match self {
+            Self::DownLeft => '┐',
+            Self::DownRight => '┌',
+            Self::LeftRight => '─',
+            Self::UpDown => '│',
+            Self::UpLeft => '┘',
+            Self::UpRight => '└',
+        }
     }
 
-    fn from_offset(self, offset: (i8, i8)) -> Option<Self> {
-        let (x, y) = offset;
-
-        match (checked_add(self.x, x), checked_add(self.y, y)) {
-            (Some(x), Some(y)) => Some(Self::new((x, y))),
-            _ => None,
+    /// The character it is parsed from.
+    const fn symbol(self) -> char {
+        match self {
+            Self::DownLeft => '7',
+            Self::DownRight => 'F',
+            Self::LeftRight => '-',
+            Self::UpDown => '|',
+            Self::UpLeft => 'J',
+            Self::UpRight => 'L',
         }
     }
 
-    fn difference(self, other: Self) -> (i8, i8) {
-        (
-            i8::try_from(self.x).unwrap() - i8::try_from(other.x).unwrap(),
-            i8::try_from(self.y).unwrap() - i8::try_from(other.y).unwrap(),
-        )
+    const fn directions(self) -> (Direction, Direction) {
+        use Direction::{Down, Left, Right, Up};
+        match self {
+            Self::DownLeft => (Down, Left),
+            Self::DownRight => (Down, Right),
+            Self::LeftRight => (Left, Right),
+            Self::UpDown => (Up, Down),
+            Self::UpLeft => (Up, Left),
+            Self::UpRight => (Up, Right),
+        }
     }
 }
 
-fn pipe_at_point_to(pipe: Pipe, current_position: Point) -> (Point, Point) {
-    let offsets = pipe.offsets();
+type Map = Grid<Option<Pipe>>;
+
+fn list_neighbors_pointing_to(map: &Map, point: Point) -> impl Iterator<Item = Point> + '_ {
+    map.neighbors8(point).filter(move |neighbor| {
+        pipe_at_point_to(map, *neighbor)
+            .is_some_and(|(left, right)| left == point || right == point)
+    })
+}
+
+/// Both ends of the pipe at `position`, if any.
+fn pipe_at_point_to(map: &Map, position: Point) -> Option<(Point, Point)> {
+    let directions = (*map.get(position)?)?.directions();
 
-    (
-        current_position.from_offset(offsets.0).unwrap(),
-        current_position.from_offset(offsets.1).unwrap(),
-    )
+    Some((position.step(directions.0)?, position.step(directions.1)?))
 }
 
-fn pipe_at_point_next(pipe: Pipe, current_position: Point, previous_position: Point) -> Point {
-    let (left, right) = pipe_at_point_to(pipe, current_position);
-    if left == previous_position {
+fn next_on_loop(
+    map: &Map,
+    current_position: Point,
+    previous_position: Point,
+) -> Result<Point, AocError> {
+    if map.get(current_position).copied().flatten().is_none() {
+        return Err(AocError::solver(format!(
+            "The loop is broken at {current_position:?}"
+        )));
+    }
+
+    let (left, right) = pipe_at_point_to(map, current_position).ok_or_else(|| {
+        AocError::solver(format!("The loop leaves the map at {current_position:?}"))
+    })?;
+    Ok(if left == previous_position {
         right
     } else {
         left
-    }
+    })
 }
 
-fn checked_add(n: u8, i: i8) -> Option<u8> {
-    let signed = u8::try_from(i.abs()).unwrap();
-
-    if i.is_negative() {
-        n.checked_sub(signed)
-    } else {
-        n.checked_add(signed)
+fn start_neighbors(map: &Map, start: Point) -> Result<(Point, Point), AocError> {
+    let mut iter = list_neighbors_pointing_to(map, start);
+    match (iter.next(), iter.next()) {
+        (Some(left), Some(right)) => Ok((left, right)),
+        _ => Err(AocError::solver("The start is not connected to two pipes")),
     }
 }
 
-mod parse {
-    use crate::advent_of_code_2023::day10::Point;
-
-    use super::Pipe;
-    use std::collections::HashMap;
+/// Tiles of the loop in order, from the start to the tile leading back to it.
+fn loop_tiles(map: &Map, start: Point) -> Result<Vec<Point>, AocError> {
+    let (mut current, _) = start_neighbors(map, start)?;
+    let mut previous = start;
+    let mut tiles = vec![start];
 
-    pub fn parse(input: &str) -> (HashMap<Point, Pipe>, Point) {
-        let mut map = HashMap::new();
-        let mut start = None;
-        for (l, y) in input.lines().zip(0u8..) {
-            let new_start = line(l, y, &mut map);
-            start = start.or(new_start);
+    while current != start {
+        if tiles.len() > map.width() * map.height() {
+            return Err(AocError::solver("The loop never closes"));
         }
+        tiles.push(current);
+        (previous, current) = (current, next_on_loop(map, current, previous)?);
+    }
+    Ok(tiles)
+}
 
-        (map, start.unwrap())
+/// Where a tile stands relative to the loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Loop(Pipe),
+    Inside,
+    Outside,
+}
+
+/// Every tile of the map, a tile off the loop being inside when the loop crosses its row an odd
+/// number of times on its left. Pipes off the loop count as ground.
+pub fn classify((map, start): &(Map, Point)) -> Result<Grid<Tile>, AocError> {
+    let tiles = loop_tiles(map, *start)?;
+    let direction = |to: Point| {
+        Direction::ALL
+            .into_iter()
+            .find(|direction| start.step(*direction) == Some(to))
+    };
+    let start_pipe = direction(tiles[1])
+        .zip(tiles.last().and_then(|last| direction(*last)))
+        .and_then(|(a, b)| Pipe::connecting(a, b))
+        .ok_or_else(|| AocError::solver("The start is not a pipe of the loop"))?;
+
+    let mut pipes = map.map(|_| None);
+    for tile in &tiles {
+        pipes[*tile] = Some(if tile == start {
+            start_pipe
+        } else {
+            map[*tile].expect("the loop to only go through pipes")
+        });
     }
 
-    fn line(input: &str, y: u8, map: &mut HashMap<Point, Pipe>) -> Option<Point> {
-        let mut maybe_start = None;
-        for (char, x) in input.bytes().zip(0u8..) {
-            let point = Point::new((x, y));
-            if let Ok(pipe) = Pipe::try_from(char) {
-                assert_eq!(map.insert(point, pipe), None);
-            } else if char == b'S' {
-                maybe_start = Some(point);
+    // a row crosses the loop at every pipe going up, `F-J` and `L-7` included
+    let mut inside = false;
+    Ok(Grid::from_fn(map.width(), map.height(), |point| {
+        if point.x == 0 {
+            inside = false;
+        }
+        match pipes[point] {
+            Some(pipe) => {
+                inside ^= pipe.connects(Direction::Up);
+                Tile::Loop(pipe)
             }
+            None if inside => Tile::Inside,
+            None => Tile::Outside,
         }
+    }))
+}
 
-        maybe_start
+/// The classified map with box-drawing pipes, `S` at the start, `█` inside and `.` outside.
+pub fn render(tiles: &Grid<Tile>, start: Point) -> String {
+    let mut rendered = tiles.map(|tile| match tile {
+        Tile::Loop(pipe) => pipe.box_drawing(),
+        Tile::Inside => '█',
+        Tile::Outside => '.',
+    });
+    if let Some(start) = rendered.get_mut(start) {
+        *start = 'S';
     }
+    rendered.render(|c| *c)
 }
 
-fn silver((map, start): (HashMap<Point, Pipe>, Point)) -> u32 {
-    let mut iter = start.list_neighbors_pointing_to_self(&map);
-    let mut left = iter.next().unwrap();
-    let mut previous_left = start;
-    let mut right = iter.next().unwrap();
-    let mut previous_right = start;
-
-    for distance in 2u32.. {
-        let new_left = pipe_at_point_next(*map.get(&left).unwrap(), left, previous_left);
-        let new_right = pipe_at_point_next(*map.get(&right).unwrap(), right, previous_right);
-
-        previous_left = left;
-        left = new_left;
-        previous_right = right;
-        right = new_right;
-
-        if left == right {
-            return distance;
+/// The loop as a cycle of tiles labelled with their pipe, pinned at their position for `neato`.
+fn loop_dot((map, start): &(Map, Point)) -> Result<Dot, AocError> {
+    let tiles = loop_tiles(map, *start)?;
+    let id = |tile: &Point| format!("{},{}", tile.x, tile.y);
+
+    let mut dot = Dot::graph("loop");
+    for tile in &tiles {
+        let position = format!("{},{}!", tile.x, map.height() - tile.y);
+        if tile == start {
+            let attributes = [("label", "S"), ("pos", &position), ("style", "filled")];
+            dot.node(&id(tile), &attributes);
+        } else {
+            let label = map[*tile].map_or('?', Pipe::symbol).to_string();
+            dot.node(&id(tile), &[("label", &label), ("pos", &position)]);
         }
     }
-
-    panic!()
-}
-
-fn shoelace_formula(angles: &[Point]) -> i64 {
-    let mut iter = angles.iter();
-    let first = iter.next().unwrap();
-    let mut previous = first;
-    let mut a = 0i64;
-    let mut b = 0i64;
-    for cur in angles.iter() {
-        a += i64::from(previous.y) * i64::from(cur.x);
-        b += i64::from(previous.x) * i64::from(cur.y);
-        previous = cur;
+    for (tile, next) in tiles.iter().zip(tiles.iter().cycle().skip(1)) {
+        dot.edge(&id(tile), &id(next), &[]);
     }
-
-    a += i64::from(previous.y) * i64::from(first.x);
-    b += i64::from(previous.x) * i64::from(first.y);
-
-    return i64::try_from(a.abs_diff(b)).unwrap() / 2 - (angles.len() as i64) / 2 + 1;
+    Ok(dot)
 }
 
-fn gold((map, start): (HashMap<Point, Pipe>, Point)) -> i64 {
-    let mut iter = start.list_neighbors_pointing_to_self(&map);
-    let mut left = iter.next().unwrap();
-    let right = iter.next().unwrap();
-    let mut previous_left = start;
-
-    let mut angles = Vec::new();
-
-    {
-        let (x, y) = right.difference(left);
-        if x != 0 && y != 0 {
-            angles.push(start);
-        }
+mod parse {
+    use super::{AocError, Map, Pipe};
+    use crate::geometry::Point;
+    use crate::grid::Grid;
+
+    pub fn parse(input: &str) -> Result<(Map, Point), AocError> {
+        let tiles = Grid::parse(input, |c| match c {
+            '.' | 'S' | '|' | '-' | 'L' | 'J' | '7' | 'F' => Ok(c),
+            _ => Err("expected a pipe, '.' or 'S'"),
+        })?;
+
+        let start = tiles
+            .iter()
+            .find(|(_, tile)| **tile == 'S')
+            .map(|(position, _)| position)
+            .ok_or_else(|| AocError::parse(input, input, "no start 'S' found"))?;
+        Ok((tiles.map(|tile| Pipe::try_from(*tile).ok()), start))
     }
+}
 
-    for _ in 1i64.. {
-        let pipe = *map.get(&left).unwrap();
-        angles.push(left);
-
-        if left == right {
-            return shoelace_formula(&angles);
-        }
-
-        let new_left = pipe_at_point_next(pipe, left, previous_left);
+/// Steps to the tile of the loop farthest from the start, half way round.
+fn silver((map, start): &(Map, Point)) -> Result<usize, AocError> {
+    Ok(loop_tiles(map, *start)?.len() / 2)
+}
 
-        previous_left = left;
-        left = new_left;
+/// Tiles enclosed by the loop going through `tiles` in order: its area by the shoelace formula,
+/// less its boundary by Pick's theorem.
+fn shoelace_formula(tiles: &[Point]) -> Result<i64, AocError> {
+    let overflow = || AocError::overflow("the area of the loop");
+    let coordinate = |n: usize| i64::try_from(n).map_err(|_| overflow());
+    let mut twice_area = 0i64;
+    for (current, next) in tiles.iter().zip(tiles.iter().cycle().skip(1)) {
+        let term = coordinate(current.x)?
+            .checked_mul(coordinate(next.y)?)
+            .zip(coordinate(current.y)?.checked_mul(coordinate(next.x)?))
+            .and_then(|(a, b)| a.checked_sub(b))
+            .ok_or_else(overflow)?;
+        twice_area = twice_area.checked_add(term).ok_or_else(overflow)?;
     }
+    let boundary = i64::try_from(tiles.len()).map_err(|_| overflow())?;
+    let area = twice_area.checked_abs().ok_or_else(overflow)? / 2;
+    Ok(area - boundary / 2 + 1)
+}
 
-    panic!("PLEASE");
+fn gold((map, start): &(Map, Point)) -> Result<i64, AocError> {
+    shoelace_formula(&loop_tiles(map, *start)?)
 }
 
 #[cfg(test)]
 mod test {
-    use super::{gold, parse::parse, silver, Point};
+    use super::{classify, gold, loop_dot, parse::parse, render, silver, Point, Tile};
 
     const SIMPLE_LOOP: &str = ".....
 .S-7.
@@ -276,20 +339,118 @@ LJ...";
 
     #[test]
     fn test_find_start() {
-        let parsed = parse(SIMPLE_LOOP);
-        assert_eq!(parsed.1, Point::new((1, 1)));
+        let parsed = parse(SIMPLE_LOOP).unwrap();
+        assert_eq!(parsed.1, Point::new(1, 1));
     }
 
     #[test]
     fn test_silver() {
-        let parsed = parse(SIMPLE_LOOP);
-        assert_eq!(silver(parsed), 4);
-        let parsed = parse(COMPLEX_LOOP);
-        assert_eq!(silver(parsed), 8);
+        let parsed = parse(SIMPLE_LOOP).unwrap();
+        assert_eq!(silver(&parsed).unwrap(), 4);
+        let parsed = parse(COMPLEX_LOOP).unwrap();
+        assert_eq!(silver(&parsed).unwrap(), 8);
     }
     #[test]
     fn test_gold() {
-        let parsed = parse(GOLD_LOOP);
-        assert_eq!(gold(parsed), 4)
+        let parsed = parse(GOLD_LOOP).unwrap();
+        assert_eq!(gold(&parsed).unwrap(), 4)
+    }
+
+    #[test]
+    fn test_broken_loop() {
+        let parsed = parse(".....\n.S-7.\n.|.|.\n.L-..\n.....").unwrap();
+        let error = gold(&parsed).unwrap_err().to_string();
+        assert!(error.contains("The loop is broken"), "{error}");
+        assert!(silver(&parsed).is_err());
+    }
+
+    const SQUEEZED_LOOP: &str = ".F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...";
+
+    const JUNK_LOOP: &str = "FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L";
+
+    fn inside_count(input: &str) -> usize {
+        let tiles = classify(&parse(input).unwrap()).unwrap();
+        tiles
+            .iter()
+            .filter(|(_, tile)| **tile == Tile::Inside)
+            .count()
+    }
+
+    #[test]
+    fn test_classify_against_shoelace() {
+        for (input, expected) in [
+            (SIMPLE_LOOP, 1),
+            (COMPLEX_LOOP, 1),
+            (GOLD_LOOP, 4),
+            (SQUEEZED_LOOP, 8),
+            (JUNK_LOOP, 10),
+        ] {
+            assert_eq!(inside_count(input), expected, "{input}");
+            assert_eq!(gold(&parse(input).unwrap()).unwrap(), expected as i64);
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let (map, start) = parse(GOLD_LOOP).unwrap();
+        let tiles = classify(&(map, start)).unwrap();
+        assert_eq!(
+            render(&tiles, start),
+            "...........
+.S───────┐.
+.│┌─────┐│.
+.││.....││.
+.││.....││.
+.│└─┐.┌─┘│.
+.│██│.│██│.
+.└──┘.└──┘.
+..........."
+        );
+    }
+
+    #[test]
+    fn test_dot() {
+        let dot = loop_dot(&parse(SIMPLE_LOOP).unwrap()).unwrap().to_string();
+        let lines: Vec<_> = dot.lines().collect();
+        assert_eq!(lines[0], "graph \"loop\" {");
+        assert_eq!(
+            lines[1],
+            "  \"1,1\" [label=\"S\", pos=\"1,4!\", style=\"filled\"];"
+        );
+        assert_eq!(lines[2], "  \"2,1\" [label=\"-\", pos=\"2,4!\"];");
+        // 8 tiles, then a closed cycle of 8 edges
+        assert_eq!(lines.len(), 1 + 8 + 8 + 1);
+        assert_eq!(lines[16], "  \"1,2\" -- \"1,1\";");
+    }
+
+    #[test]
+    fn test_large_loop() {
+        const SIZE: usize = 300;
+        let mut lines = vec![format!("|{}|", ".".repeat(SIZE - 2)); SIZE];
+        lines[0] = format!("S{}7", "-".repeat(SIZE - 2));
+        lines[SIZE - 1] = format!("L{}J", "-".repeat(SIZE - 2));
+        let parsed = parse(&lines.join("\n")).unwrap();
+
+        assert_eq!(silver(&parsed).unwrap(), 2 * (SIZE - 1));
+        assert_eq!(gold(&parsed).unwrap(), ((SIZE - 2) * (SIZE - 2)) as i64);
+        assert_eq!(inside_count(&lines.join("\n")), (SIZE - 2) * (SIZE - 2));
     }
 }
@@ -1,97 +1,80 @@
-use crate::{aoc_result::AOCResult, read_file::read_file};
+use crate::{
+    aoc_error::AocError,
+    checked,
+    geometry::Point,
+    grid::Grid,
+    solver::{PartOutput, Solver},
+};
 
-pub fn solve() -> AOCResult {
-    let input = read_file(2023, 11).expect("File input/2023/11.txt");
-    let parsed = parse::parse(&input);
+pub struct Day11;
 
-    (silver(parsed.clone()), gold(parsed)).into()
+impl Solver for Day11 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 11;
+
+    type Input = MilkyWay;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse::parse(input)
+    }
+
+    fn silver(input: &Self::Input) -> impl PartOutput {
+        silver(input)
+    }
+
+    fn gold(input: &Self::Input) -> impl PartOutput {
+        gold(input)
+    }
 }
 
-fn silver(sky: MilkyWay) -> usize {
+fn silver(sky: &MilkyWay) -> Result<usize, AocError> {
     let points = get_galaxies_with_empty_offset(sky, 1);
     find_manhatan_distance_for_each(&points)
 }
 
-fn gold(sky: MilkyWay) -> usize {
-    let points = dbg!(get_galaxies_with_empty_offset(sky, 999999));
+fn gold(sky: &MilkyWay) -> Result<usize, AocError> {
+    let points = get_galaxies_with_empty_offset(sky, 999_999);
 
     find_manhatan_distance_for_each(&points)
 }
 
 #[derive(Debug, Copy, Clone)]
-struct Galaxy {}
+pub struct Galaxy {}
 
-type MilkyWay = Vec<Vec<Option<Galaxy>>>;
+type MilkyWay = Grid<Option<Galaxy>>;
 
 mod parse {
-    use nom::IResult;
-
-    use super::Galaxy;
-
-    pub fn parse(input: &str) -> Vec<Vec<Option<Galaxy>>> {
-        internal(input).unwrap().1
-    }
-
-    fn internal(input: &str) -> IResult<&str, Vec<Vec<Option<Galaxy>>>> {
-        let (input, elem) = nom::multi::separated_list1(
-            nom::character::complete::newline,
-            nom::multi::fold_many1(
-                nom::character::complete::one_of(".#"),
-                Vec::new,
-                |mut acc: Vec<Option<Galaxy>>, elem: char| {
-                    match elem {
-                        '.' => acc.push(None),
-                        '#' => acc.push(Some(Galaxy {})),
-                        _ => panic!("unexpected element {elem}, expected either '.' or '#'"),
-                    }
-
-                    acc
-                },
-            ),
-        )(input)?;
-
-        Ok((input, elem))
+    use super::{AocError, Galaxy, MilkyWay};
+    use crate::grid::Grid;
+
+    pub fn parse(input: &str) -> Result<MilkyWay, AocError> {
+        Grid::parse(input, |c| match c {
+            '.' => Ok(None),
+            '#' => Ok(Some(Galaxy {})),
+            _ => Err("expected either '.' or '#'"),
+        })
     }
 }
 
-fn list_empty_lines(sky: MilkyWay) -> (Vec<usize>, Vec<usize>) {
-    let x_result = list_x_empty_line(&sky);
-    let sky = rotate(sky);
-    let y_result = list_x_empty_line(&sky);
-
-    (x_result, y_result)
-}
-
-fn list_x_empty_line(sky: &[Vec<Option<Galaxy>>]) -> Vec<usize> {
-    sky.iter()
+/// Indices of the lines without any galaxy.
+fn list_empty_lines<'a, L>(lines: impl Iterator<Item = L>) -> Vec<usize>
+where
+    L: IntoIterator<Item = &'a Option<Galaxy>>,
+{
+    lines
         .enumerate()
-        .filter_map(|(x, line)| {
-            if line.iter().all(Option::is_none) {
-                Some(x)
-            } else {
-                None
-            }
-        })
+        .filter_map(|(index, line)| line.into_iter().all(Option::is_none).then_some(index))
         .collect()
 }
 
-fn get_galaxies_with_empty_offset(sky: MilkyWay, offset: usize) -> Vec<(usize, usize)> {
-    let (x_empty, y_empty) = list_empty_lines(sky.clone());
-
-    let points =
-        sky.into_iter().enumerate().flat_map(|(x, line)| {
-            line.into_iter().enumerate().filter_map(move |(y, elem)| {
-                if elem.is_some() {
-                    Some((x, y))
-                } else {
-                    None
-                }
-            })
-        });
-
-    points
-        .map(|(x, y)| {
-            (
+fn get_galaxies_with_empty_offset(sky: &MilkyWay, offset: usize) -> Vec<Point> {
+    let x_empty = list_empty_lines(sky.columns());
+    let y_empty = list_empty_lines(sky.rows());
+
+    sky.iter()
+        .filter(|(_, elem)| elem.is_some())
+        .map(|(Point { x, y }, _)| {
+            Point::new(
                 x + (x_empty.iter().filter(|empty| x > **empty).count() * offset),
                 y + (y_empty.iter().filter(|empty| y > **empty).count() * offset),
             )
@@ -99,41 +82,22 @@ fn get_galaxies_with_empty_offset(sky: MilkyWay, offset: usize) -> Vec<(usize, u
         .collect()
 }
 
-fn rotate(sky: MilkyWay) -> MilkyWay {
-    let mut result: MilkyWay = Vec::new();
-    let x_len = sky.len();
-    let y_len = sky.first().unwrap().len();
-    for y in 0..y_len {
-        let mut line = Vec::new();
-        for x in 0..x_len {
-            line.push(sky.get(x).unwrap().get(y).unwrap().to_owned());
-        }
-        result.push(line);
-    }
-
-    result
-}
-
-fn find_manhatan_distance_for_each(points: &[(usize, usize)]) -> usize {
-    points
-        .iter()
-        .enumerate()
-        .map(|(index, point)| {
+fn find_manhatan_distance_for_each(points: &[Point]) -> Result<usize, AocError> {
+    checked::sum(
+        points.iter().enumerate().flat_map(|(index, point)| {
             points[(index + 1)..]
                 .iter()
-                .map(|other| manhattan_distance(*point, *other))
-                .sum::<usize>()
-        })
-        .sum()
-}
-
-fn manhattan_distance((ax, ay): (usize, usize), (bx, by): (usize, usize)) -> usize {
-    ax.abs_diff(bx) + ay.abs_diff(by)
+                .map(|other| point.manhattan_distance(*other))
+        }),
+        "the sum of distances between galaxies",
+    )
 }
 
 #[cfg(test)]
 mod test {
-    use super::{parse::parse, silver};
+    use super::{
+        find_manhatan_distance_for_each, get_galaxies_with_empty_offset, gold, parse::parse, silver,
+    };
 
     const INPUT: &str = "...#......
 .......#..
@@ -148,7 +112,27 @@ mod test {
 
     #[test]
     fn test_silver() {
-        let galaxy = parse(INPUT);
-        assert_eq!(silver(galaxy), 374)
+        let galaxy = parse(INPUT).unwrap();
+        assert_eq!(silver(&galaxy).unwrap(), 374)
+    }
+
+    #[test]
+    fn test_older_galaxies() {
+        let galaxy = parse(INPUT).unwrap();
+        let points = get_galaxies_with_empty_offset(&galaxy, 9);
+        assert_eq!(find_manhatan_distance_for_each(&points).unwrap(), 1030);
+        let points = get_galaxies_with_empty_offset(&galaxy, 99);
+        assert_eq!(find_manhatan_distance_for_each(&points).unwrap(), 8410);
+    }
+
+    #[test]
+    fn test_large_sky() {
+        let mut rows = vec![".".repeat(300); 300];
+        rows[0].replace_range(0..1, "#");
+        rows[299].replace_range(299..300, "#");
+        let galaxy = parse(&rows.join("\n")).unwrap();
+
+        assert_eq!(silver(&galaxy).unwrap(), 2 * (299 + 298));
+        assert_eq!(gold(&galaxy).unwrap(), 2 * (299 + 298 * 999_999));
     }
 }
@@ -1,160 +1,269 @@
+use std::fmt;
+
 use nom::{
     bytes::complete::tag,
     character::complete::{newline, one_of},
-    multi, sequence, IResult,
+    error::context,
+    multi, sequence,
+};
+
+use crate::{
+    aoc_error::AocError,
+    checked,
+    diagnostic::{self, ParseResult},
+    solver::{DumpOptions, PartOutput, Solver},
 };
 
-use crate::{aoc_result::AOCResult, read_file::read_file};
+pub struct Day12;
+
+impl Solver for Day12 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 12;
+
+    type Input = Vec<Line>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
+
+    fn silver(input: &Self::Input) -> impl PartOutput {
+        silver(input)
+    }
+
+    fn gold(input: &Self::Input) -> impl PartOutput {
+        gold(input)
+    }
+
+    fn dump(input: &Self::Input, options: DumpOptions) -> Result<Option<String>, AocError> {
+        dump(input, options).map(Some)
+    }
+}
+
+/// How many copies of each row the unfolded records hold.
+const UNFOLD: usize = 5;
 
-pub fn solve() -> AOCResult {
-    let input = read_file(2023, 12).expect("Read file should be correct for 2023 12");
-    let parsed = parse(&input);
-    (silver(&parsed), 0).into()
+fn silver(lines: &[Line]) -> Result<u64, AocError> {
+    total_arrangements(lines.iter().map(Line::arrangements))
 }
 
-fn silver(lines: &[Line]) -> usize {
-    lines
-        .iter()
-        .map(|line| {
-            let count = line
-                .silver_possibilities()
-                .map(|possibility| line.is_possible_with(possibility))
-                .filter(|e| *e)
-                .count();
-
-            if count == 0 {
-                1
-            } else {
-                count
-            }
-        })
-        .sum()
+fn gold(lines: &[Line]) -> Result<u64, AocError> {
+    total_arrangements(lines.iter().map(|line| line.unfold(UNFOLD).arrangements()))
+}
+
+fn total_arrangements(
+    counts: impl Iterator<Item = Result<u64, AocError>>,
+) -> Result<u64, AocError> {
+    let counts = counts.collect::<Result<Vec<_>, AocError>>()?;
+    checked::sum(counts, "the sum of arrangements")
+}
+
+/// Every row with its number of arrangements, followed by the first ones unless only counting.
+fn dump(lines: &[Line], options: DumpOptions) -> Result<String, AocError> {
+    let mut dump = Vec::new();
+    for line in lines {
+        if options.count_only {
+            dump.push(format!("{line}: {} arrangement(s)", line.arrangements()?));
+            continue;
+        }
+
+        let (count, arrangements) = line.render_arrangements(options.limit)?;
+        dump.push(format!("{line}: {count} arrangement(s)"));
+        let hidden = count - arrangements.len() as u64;
+        dump.extend(arrangements.into_iter().map(|row| format!("  {row}")));
+        if hidden > 0 {
+            dump.push(format!("  ... and {hidden} more"));
+        }
+    }
+    Ok(dump.join("\n"))
 }
 
 #[derive(Debug)]
-struct Line {
+pub struct Line {
     history: Vec<Option<State>>,
-    group_damaged: Vec<u8>,
+    group_damaged: Vec<usize>,
 }
 
 impl Line {
-    fn is_possible_with(&self, mut possibility: SilverPossibility) -> bool {
-        let group_damaged = self
-            .history
-            .iter()
-            .map(|state| match state {
-                None => possibility.next().unwrap(),
-                Some(state) => *state,
-            })
-            .fold(
-                (Vec::new(), State::Working),
-                |(mut acc, previous_state): (Vec<u8>, State), cur| {
-                    match (cur, previous_state) {
-                        (State::Working, _) => (),
-                        (State::Broken, State::Broken) => {
-                            *acc.last_mut().unwrap() += 1;
-                        }
-                        (State::Broken, State::Working) => {
-                            acc.push(1);
-                        }
-                    };
-
-                    (acc, cur)
-                },
-            )
-            .0;
-
-        group_damaged == self.group_damaged
-    }
-
-    fn silver_possibilities(&self) -> impl Iterator<Item = SilverPossibility> {
-        let nbr_damaged: u8 = self.group_damaged.iter().sum();
-        let already_damaged_in_history = u8::try_from(
-            self.history
-                .iter()
-                .filter(|state| **state == Some(State::Broken))
-                .count(),
-        )
-        .unwrap();
-
-        let missing_damaged = u32::from(nbr_damaged - already_damaged_in_history);
-        let questionmark_in_history =
-            u8::try_from(self.history.iter().filter(|state| **state == None).count()).unwrap();
-
-        (1..2u32.pow(u32::from(questionmark_in_history)))
-            .into_iter()
-            .filter(move |index| index.count_ones() == missing_damaged)
-            .map(SilverPossibility::from)
+    /// Ways to fill the unknown springs so that the damaged ones form the expected groups, which
+    /// is 0 when the row contradicts its groups.
+    pub fn arrangements(&self) -> Result<u64, AocError> {
+        Ok(self.ways(&self.damaged_runs())?[0][0])
     }
-}
 
-impl From<(Vec<Option<State>>, Vec<u8>)> for Line {
-    fn from((history, group_damaged): (Vec<Option<State>>, Vec<u8>)) -> Self {
-        Line {
-            history,
-            group_damaged,
+    /// Number of arrangements, and the first `limit` ones as rows of `.` and `#`, sorted with `.`
+    /// before `#`.
+    pub fn render_arrangements(&self, limit: usize) -> Result<(u64, Vec<String>), AocError> {
+        let runs = self.damaged_runs();
+        let ways = self.ways(&runs)?;
+        let len = self.history.len();
+
+        let mut arrangements = Vec::new();
+        // only states leading to at least one arrangement are pushed, so none is a dead end
+        let mut stack = Vec::new();
+        if ways[0][0] > 0 {
+            stack.push((0, 0, String::new()));
         }
+        while let Some((group, index, row)) = stack.pop() {
+            if arrangements.len() == limit {
+                break;
+            }
+            if index == len {
+                arrangements.push(row);
+                continue;
+            }
+
+            if let Some(&size) = self.group_damaged.get(group) {
+                let next = (index + size + 1).min(len);
+                if self.fits(&runs, index, size) && ways[group + 1][next] > 0 {
+                    let mut row = row.clone() + &"#".repeat(size);
+                    if next > index + size {
+                        row.push('.');
+                    }
+                    stack.push((group + 1, next, row));
+                }
+            }
+            if self.history[index] != Some(State::Broken) && ways[group][index + 1] > 0 {
+                stack.push((group, index + 1, row + "."));
+            }
+        }
+        Ok((ways[0][0], arrangements))
     }
-}
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
-enum State {
-    Working,
-    Broken,
-}
+    /// Number of springs from each position on that may all be damaged.
+    fn damaged_runs(&self) -> Vec<usize> {
+        let mut runs = vec![0; self.history.len() + 1];
+        for (index, state) in self.history.iter().enumerate().rev() {
+            if *state != Some(State::Working) {
+                runs[index] = runs[index + 1] + 1;
+            }
+        }
+        runs
+    }
 
-struct SilverPossibility {
-    is_broken_list: u32,
-}
+    /// Whether a group of `size` damaged springs can start at `index`, followed by a working
+    /// spring or the end of the row.
+    fn fits(&self, runs: &[usize], index: usize, size: usize) -> bool {
+        runs[index] >= size && self.history.get(index + size) != Some(&Some(State::Broken))
+    }
+
+    /// `ways[group][index]` counts the arrangements of `group_damaged[group..]` in
+    /// `history[index..]`.
+    fn ways(&self, runs: &[usize]) -> Result<Vec<Vec<u64>>, AocError> {
+        let history = &self.history;
+        let len = history.len();
+
+        // with no group left, every spring must be working
+        let mut last = vec![0u64; len + 1];
+        last[len] = 1;
+        for index in (0..len).rev() {
+            if history[index] != Some(State::Broken) {
+                last[index] = last[index + 1];
+            }
+        }
+
+        let mut ways = vec![last];
+        for &size in self.group_damaged.iter().rev() {
+            let after = &ways[ways.len() - 1];
+            let mut current = vec![0u64; len + 1];
+            for index in (0..len).rev() {
+                let mut count = 0;
+                if history[index] != Some(State::Broken) {
+                    count = current[index + 1];
+                }
+                if self.fits(runs, index, size) {
+                    count = count
+                        .checked_add(after[(index + size + 1).min(len)])
+                        .ok_or_else(|| AocError::overflow("the arrangements of a row"))?;
+                }
+                current[index] = count;
+            }
+            ways.push(current);
+        }
 
-impl From<u32> for SilverPossibility {
-    fn from(value: u32) -> Self {
+        ways.reverse();
+        Ok(ways)
+    }
+
+    /// `times` copies of the row separated by an unknown spring, and of its groups.
+    fn unfold(&self, times: usize) -> Self {
+        let history = vec![self.history.clone(); times].join(&None);
         Self {
-            is_broken_list: value,
+            history,
+            group_damaged: self.group_damaged.repeat(times),
         }
     }
 }
 
-impl Iterator for SilverPossibility {
-    type Item = State;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let is_broken = (self.is_broken_list & 1) == 1;
-        self.is_broken_list = self.is_broken_list >> 1;
+impl From<(Vec<Option<State>>, Vec<usize>)> for Line {
+    fn from((history, group_damaged): (Vec<Option<State>>, Vec<usize>)) -> Self {
+        Line {
+            history,
+            group_damaged,
+        }
+    }
+}
 
-        Some(if is_broken {
-            State::Broken
-        } else {
-            State::Working
-        })
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for state in &self.history {
+            let spring = match state {
+                None => '?',
+                Some(State::Working) => '.',
+                Some(State::Broken) => '#',
+            };
+            write!(f, "{spring}")?;
+        }
+        let groups: Vec<_> = self.group_damaged.iter().map(usize::to_string).collect();
+        write!(f, " {}", groups.join(","))
     }
 }
 
-fn parse(input: &str) -> Vec<Line> {
-    parse_internal(input).unwrap().1
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum State {
+    Working,
+    Broken,
+}
+
+fn parse(input: &str) -> Result<Vec<Line>, AocError> {
+    diagnostic::parse_with(input, parse_internal)
 }
 
-fn parse_internal(input: &str) -> IResult<&str, Vec<Line>> {
+fn parse_internal(input: &str) -> ParseResult<'_, Vec<Line>> {
     let (input, result) = multi::separated_list1(
         newline,
         sequence::pair(
-            multi::fold_many1(
-                one_of("?.#"),
-                Vec::new,
-                |mut acc: Vec<Option<State>>, elem: char| {
-                    acc.push(match elem {
-                        '?' => None,
-                        '.' => Some(State::Working),
-                        '#' => Some(State::Broken),
-                        _ => panic!("Unexpected elem, should not happen"),
-                    });
-
-                    acc
-                },
+            context(
+                "spring conditions",
+                multi::fold_many1(
+                    one_of("?.#"),
+                    Vec::new,
+                    |mut acc: Vec<Option<State>>, elem: char| {
+                        acc.push(match elem {
+                            '?' => None,
+                            '.' => Some(State::Working),
+                            '#' => Some(State::Broken),
+                            _ => panic!("Unexpected elem, should not happen"),
+                        });
+
+                        acc
+                    },
+                ),
             ),
-            sequence::preceded(
-                tag(" "),
-                multi::separated_list1(tag(","), nom::character::complete::u8),
+            context(
+                "damaged group sizes",
+                sequence::preceded(
+                    tag(" "),
+                    multi::separated_list1(
+                        tag(","),
+                        nom::combinator::verify(
+                            nom::combinator::map_res(
+                                nom::character::complete::u64,
+                                usize::try_from,
+                            ),
+                            |group: &usize| *group > 0,
+                        ),
+                    ),
+                ),
             ),
         ),
     )(input)?;
@@ -164,7 +273,8 @@ fn parse_internal(input: &str) -> IResult<&str, Vec<Line>> {
 
 #[cfg(test)]
 mod test {
-    use super::{parse, silver};
+    use super::{gold, parse, silver, Day12};
+    use crate::solver::{DumpOptions, Puzzle};
 
     const INPUT: &str = "???.### 1,1,3
 .??..??...?##. 1,1,3
@@ -175,13 +285,92 @@ mod test {
 
     #[test]
     fn test_silver() {
-        let parsed = parse(INPUT);
-        assert_eq!(silver(&parsed), 21);
+        let parsed = parse(INPUT).unwrap();
+        assert_eq!(silver(&parsed).unwrap(), 21);
+    }
+
+    #[test]
+    fn test_gold() {
+        let parsed = parse(INPUT).unwrap();
+        assert_eq!(gold(&parsed).unwrap(), 525_152);
+
+        let counts: Vec<_> = parsed
+            .iter()
+            .map(|line| line.unfold(5).arrangements().unwrap())
+            .collect();
+        assert_eq!(counts, [1, 16_384, 1, 16, 2_500, 506_250]);
+    }
+
+    #[test]
+    fn test_render_arrangements() {
+        let parsed = parse(INPUT).unwrap();
+        let (count, rows) = parsed[5].render_arrangements(usize::MAX).unwrap();
+        assert_eq!(count, 10);
+        assert_eq!(
+            rows,
+            [
+                ".###....##.#",
+                ".###...##..#",
+                ".###...##.#.",
+                ".###..##...#",
+                ".###..##..#.",
+                ".###..##.#..",
+                ".###.##....#",
+                ".###.##...#.",
+                ".###.##..#..",
+                ".###.##.#...",
+            ]
+        );
+
+        for line in &parsed {
+            let unfolded = line.unfold(2);
+            let (count, rows) = unfolded.render_arrangements(usize::MAX).unwrap();
+            assert_eq!(count, unfolded.arrangements().unwrap());
+            assert_eq!(rows.len() as u64, count);
+            // '.' sorts after '#' in ASCII
+            assert!(rows.windows(2).all(|pair| pair[0] > pair[1]));
+            assert!(rows.iter().all(|row| row.len() == unfolded.history.len()));
+        }
+        assert!(parse("#.# 1").unwrap()[0]
+            .render_arrangements(10)
+            .unwrap()
+            .1
+            .is_empty());
+    }
+
+    #[test]
+    fn test_dump() {
+        let options = DumpOptions {
+            limit: 2,
+            count_only: false,
+        };
+        let dump = Day12
+            .dump(".??..??...?##. 1,1,3", options)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            dump,
+            ".??..??...?##. 1,1,3: 4 arrangement(s)
+  ..#...#...###.
+  ..#..#....###.
+  ... and 2 more"
+        );
+
+        let options = DumpOptions {
+            limit: 2,
+            count_only: true,
+        };
+        let dump = Day12.dump(INPUT, options).unwrap().unwrap();
+        assert_eq!(dump.lines().count(), 6);
+        assert!(dump.ends_with("?###???????? 3,2,1: 10 arrangement(s)"));
     }
 
     #[test]
     fn parse_line() {
         // No possibility because no question mark to do...
-        assert_eq!(silver(&parse("??.#???.#? 1,1")), 1);
+        assert_eq!(silver(&parse("??.#???.#? 1,1").unwrap()).unwrap(), 1);
+        // a row contradicting its groups has no arrangement at all
+        assert_eq!(silver(&parse("#.# 1").unwrap()).unwrap(), 0);
+        assert!(parse("??? 1,0").is_err());
     }
 }
@@ -1,52 +1,139 @@
-use std::collections::HashMap;
+use crate::{
+    aoc_error::AocError,
+    checked,
+    grid::Grid,
+    solver::{PartOutput, Solver},
+};
 
-use nom::IResult;
+pub struct Day13;
 
-use crate::{aoc_result::AOCResult, read_file::read_file};
+impl Solver for Day13 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 13;
 
-pub fn solve() -> AOCResult {
-    let input = read_file(2023, 13).expect("Read file should be correct for 2023 13");
-    let parsed = parse(&input);
-    (0, 0).into()
-}
+    type Input = Vec<MirrorMaze>;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-struct Rock {}
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
 
-type MirrorMaze = HashMap<(u8, u8), Option<Rock>>;
+    fn silver(input: &Self::Input) -> impl PartOutput {
+        summarize(input, 0)
+    }
 
-fn parse(input: &str) -> Vec<MirrorMaze> {
-    parse_internal(input).unwrap().1
+    fn gold(input: &Self::Input) -> impl PartOutput {
+        summarize(input, 1)
+    }
 }
 
-fn parse_internal(input: &str) -> IResult<&str, Vec<MirrorMaze>> {
-    let (input, mazes) = nom::multi::separated_list1(
-        nom::character::complete::newline,
-        nom::multi::separated_list1(
-            nom::character::complete::newline,
-            nom::character::complete::one_of("#."),
-        ),
-    )(input)?;
-
-    let mazes = mazes
-        .into_iter()
-        .zip(0u8..)
-        .map(|(maze, x)| {
-            maze.into_iter()
-                .zip(0u8..)
-                .fold(HashMap::new(), |mut acc, (rock, y)| {
-                    let maybe_rock = match rock {
-                        '#' => Some(Rock {}),
-                        '.' => None,
-                        _ => panic!("should not happen. {rock} was something else than '#' or '.'"),
-                    };
-
-                    assert_eq!(acc.insert((x, y), maybe_rock), None);
-
-                    acc
+/// Sum of the columns left of each vertical line of reflection and 100 times the rows above
+/// each horizontal one, the reflection only holding once `smudges` cells are fixed.
+fn summarize(mazes: &[MirrorMaze], smudges: usize) -> Result<usize, AocError> {
+    let notes = mazes
+        .iter()
+        .enumerate()
+        .map(|(index, maze)| {
+            let horizontal = reflection(maze.rows(), smudges).map(|rows| 100 * rows);
+            horizontal
+                .or_else(|| reflection(maze.transpose().rows(), smudges))
+                .ok_or_else(|| {
+                    AocError::solver(format!(
+                        "pattern {} has no line of reflection with {smudges} smudge(s)",
+                        index + 1
+                    ))
                 })
         })
-        .collect();
+        .collect::<Result<Vec<_>, AocError>>()?;
+    checked::sum(notes, "the summary of the notes")
+}
+
+/// Number of lines before the first line of reflection differing in exactly `smudges` cells.
+fn reflection<'a>(
+    lines: impl Iterator<Item = &'a [Option<Rock>]>,
+    smudges: usize,
+) -> Option<usize> {
+    let lines: Vec<_> = lines.collect();
+    (1..lines.len()).find(|&before| {
+        let mut differences = 0;
+        // stop at the first pair of lines going over the smudges
+        let within = lines[..before]
+            .iter()
+            .rev()
+            .zip(&lines[before..])
+            .all(|(a, b)| {
+                differences += a.iter().zip(*b).filter(|(a, b)| a != b).count();
+                differences <= smudges
+            });
+        within && differences == smudges
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Rock {}
+
+type MirrorMaze = Grid<Option<Rock>>;
+
+fn parse(input: &str) -> Result<Vec<MirrorMaze>, AocError> {
+    Grid::parse_many(input, |c| match c {
+        '#' => Ok(Some(Rock {})),
+        '.' => Ok(None),
+        _ => Err("expected either '#' or '.'"),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, summarize};
+    use crate::geometry::Point;
+
+    const INPUT: &str = "#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#";
+
+    #[test]
+    fn test_parse() {
+        let mazes = parse(INPUT).unwrap();
+        assert_eq!(mazes.len(), 2);
+        assert_eq!((mazes[0].width(), mazes[0].height()), (9, 7));
+        assert_eq!((mazes[1].width(), mazes[1].height()), (9, 7));
+        assert!(mazes[1][Point::new(0, 0)].is_some());
+    }
+
+    #[test]
+    fn test_silver() {
+        let mazes = parse(INPUT).unwrap();
+        assert_eq!(summarize(&mazes[..1], 0).unwrap(), 5);
+        assert_eq!(summarize(&mazes[1..], 0).unwrap(), 400);
+        assert_eq!(summarize(&mazes, 0).unwrap(), 405);
+    }
+
+    #[test]
+    fn test_gold() {
+        let mazes = parse(INPUT).unwrap();
+        assert_eq!(summarize(&mazes[..1], 1).unwrap(), 300);
+        assert_eq!(summarize(&mazes[1..], 1).unwrap(), 100);
+        assert_eq!(summarize(&mazes, 1).unwrap(), 400);
+    }
 
-    Ok((input, mazes))
+    #[test]
+    fn test_no_reflection() {
+        let mazes = parse("#.\n.#\n\n##\n##").unwrap();
+        let error = summarize(&mazes, 0).unwrap_err().to_string();
+        assert!(
+            error.ends_with("pattern 1 has no line of reflection with 0 smudge(s)"),
+            "{error}"
+        );
+    }
 }
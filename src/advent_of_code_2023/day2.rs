@@ -1,40 +1,60 @@
-use crate::aoc_result::AOCResult;
-use crate::read_file::read_file;
+use crate::{
+    aoc_error::AocError,
+    checked,
+    solver::{PartOutput, Solver},
+};
 
-pub fn solve() -> AOCResult {
-    let content = read_file(2023, 2).expect("File input/2023/02.txt to exist");
-    let parsed = content.lines().map(parse::line).map(Option::unwrap);
+pub struct Day2;
 
-    (silver(parsed.clone()), gold(parsed)).into()
+impl Solver for Day2 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 2;
+
+    type Input = Vec<LineInfo>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse::parse(input)
+    }
+
+    fn silver(input: &Self::Input) -> impl PartOutput {
+        silver(input.iter().copied())
+    }
+
+    fn gold(input: &Self::Input) -> impl PartOutput {
+        gold(input.iter().copied())
+    }
 }
 
-type LineInfo = (u8, Line);
+type LineInfo = (u32, Line);
 
-fn silver(lines: impl Iterator<Item = LineInfo>) -> u32 {
+fn silver(lines: impl Iterator<Item = LineInfo>) -> Result<u64, AocError> {
     const ESTIMATED_BAG: Line = Line::rgb(12, 13, 14);
 
-    lines.fold(0, |acc, (game_id, line)| {
-        if line.game_is_possible_with(ESTIMATED_BAG) {
-            acc + u32::from(game_id)
-        } else {
-            acc
-        }
-    })
+    checked::sum(
+        lines
+            .filter(|(_, line)| line.game_is_possible_with(ESTIMATED_BAG))
+            .map(|(game_id, _)| u64::from(game_id)),
+        "the sum of game ids",
+    )
 }
 
-fn gold(lines: impl Iterator<Item = LineInfo>) -> u32 {
-    lines.map(|(_, line)| line).map(Line::gold).sum()
+fn gold(lines: impl Iterator<Item = LineInfo>) -> Result<u64, AocError> {
+    let powers = lines
+        .map(|(_, line)| line.gold())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| AocError::overflow("the power of a set of cubes"))?;
+    checked::sum(powers, "the sum of powers")
 }
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd, Eq)]
-struct Line {
-    red: u8,
-    green: u8,
-    blue: u8,
+pub struct Line {
+    red: u32,
+    green: u32,
+    blue: u32,
 }
 
 impl Line {
-    const fn rgb(r: u8, g: u8, b: u8) -> Self {
+    const fn rgb(r: u32, g: u32, b: u32) -> Self {
         Self {
             red: r,
             green: g,
@@ -46,35 +66,55 @@ impl Line {
         self.red <= bag.red && self.green <= bag.green && self.blue <= bag.blue
     }
 
-    fn gold(self) -> u32 {
-        u32::from(self.red) * u32::from(self.green) * u32::from(self.blue)
+    /// Power of the set, `None` when it overflows.
+    fn gold(self) -> Option<u64> {
+        (u64::from(self.red) * u64::from(self.green)).checked_mul(u64::from(self.blue))
     }
 }
 
 mod parse {
-    use nom::IResult;
+    use nom::error::context;
 
-    use super::Line;
+    use super::{AocError, Line};
+    use crate::diagnostic::{self, ParseResult};
 
-    pub fn line(input: &str) -> Option<(u8, Line)> {
-        line_internal(input).ok().map(|(_, value)| value)
+    pub fn parse(input: &str) -> Result<Vec<(u32, Line)>, AocError> {
+        diagnostic::parse_with(
+            input,
+            nom::multi::separated_list1(nom::character::complete::newline, line),
+        )
     }
 
-    fn line_internal(input: &str) -> IResult<&str, (u8, Line)> {
-        let (input, _) = nom::bytes::complete::tag("Game ")(input)?;
-        let (input, game_id) = nom::character::complete::u8(input)?;
-        let (input, _) = nom::bytes::complete::tag(": ")(input)?;
+    fn line(input: &str) -> ParseResult<'_, (u32, Line)> {
+        let (input, game_id) = context(
+            "game header",
+            nom::sequence::delimited(
+                nom::bytes::complete::tag("Game "),
+                nom::character::complete::u32,
+                nom::bytes::complete::tag(": "),
+            ),
+        )(input)?;
         let (input, colors) = nom::multi::separated_list1(
             nom::sequence::tuple((
                 nom::character::complete::one_of(";,"),
                 nom::bytes::complete::tag(" "),
             )),
             nom::sequence::tuple((
-                nom::sequence::terminated(
-                    nom::character::complete::u8,
-                    nom::bytes::complete::tag(" "),
+                context(
+                    "cube count",
+                    nom::sequence::terminated(
+                        nom::character::complete::u32,
+                        nom::bytes::complete::tag(" "),
+                    ),
+                ),
+                context(
+                    "cube color",
+                    nom::branch::alt((
+                        nom::bytes::complete::tag("red"),
+                        nom::bytes::complete::tag("green"),
+                        nom::bytes::complete::tag("blue"),
+                    )),
                 ),
-                nom::character::complete::alpha1,
             )),
         )(input)?;
 
@@ -83,8 +123,7 @@ mod parse {
             match color {
                 "green" => line.green = line.green.max(num),
                 "red" => line.red = line.red.max(num),
-                "blue" => line.blue = line.blue.max(num),
-                _ => panic!(r#"Color should be one of "red"|"green"|"blue", but got {color}"#),
+                _ => line.blue = line.blue.max(num),
             }
         }
 
@@ -94,7 +133,7 @@ mod parse {
 
 #[cfg(test)]
 mod test {
-    use super::{gold, parse::line, silver, Line, LineInfo};
+    use super::{gold, parse::parse, silver, Line, LineInfo};
 
     const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
 Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
@@ -118,19 +157,30 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
         );
     }
 
+    #[test]
+    fn test_parse_trailing_text() {
+        let error = parse("Game 1: 13 red\nGame 2: 13 red; 1 blue x")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.starts_with("Parse error at line 2, column 23: expected end of input, found ' '"),
+            "{error}"
+        );
+    }
+
     #[test]
     fn test_silver() {
         let lines = test_parse();
-        assert_eq!(silver(lines), 8);
+        assert_eq!(silver(lines).unwrap(), 8);
     }
 
     #[test]
     fn test_gold() {
         let lines = test_parse();
-        assert_eq!(gold(lines), 2286);
+        assert_eq!(gold(lines).unwrap(), 2286);
     }
 
     fn test_parse() -> impl Iterator<Item = LineInfo> {
-        INPUT.lines().map(line).map(Option::unwrap)
+        parse(INPUT).unwrap().into_iter()
     }
 }
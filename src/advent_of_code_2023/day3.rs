@@ -1,107 +1,87 @@
-use crate::aoc_result::AOCResult;
-use crate::read_file::read_file;
+use crate::{
+    aoc_error::AocError,
+    checked,
+    geometry::Point,
+    solver::{PartOutput, Solver},
+};
 
-pub fn solve() -> AOCResult {
-    let content = read_file(2023, 3).expect("File input/2023/03.txt to exist");
-    let parsed = parse::parse(&content).unwrap();
+pub struct Day3;
 
-    (silver(&parsed), gold(&parsed)).into()
+impl Solver for Day3 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 3;
+
+    type Input = Input;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse::parse(input)
+    }
+
+    fn silver(input: &Self::Input) -> impl PartOutput {
+        silver(input)
+    }
+
+    fn gold(input: &Self::Input) -> impl PartOutput {
+        gold(input)
+    }
 }
 
-fn silver(input: &Input) -> u32 {
-    input
-        .part_numbers_next_to_symbol()
-        .map(|part| u32::from(part.value))
-        .sum()
+fn silver(input: &Input) -> Result<u64, AocError> {
+    checked::sum(
+        input.part_numbers_next_to_symbol().map(|part| part.value),
+        "the sum of part numbers",
+    )
 }
 
-fn gold(input: &Input) -> u32 {
-    input.gear_ratio_per_symbol().sum()
+fn gold(input: &Input) -> Result<u64, AocError> {
+    let ratios = input
+        .gear_ratio_per_symbol()
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| AocError::overflow("a gear ratio"))?;
+    checked::sum(ratios, "the sum of gear ratios")
 }
 
 #[derive(Debug, PartialEq, Default)]
-struct Input {
+pub struct Input {
     parts: Vec<PartNumber>,
     symbols: Vec<Point>,
 }
 
 impl Input {
-    fn add_symbol(&mut self, position: Point) {
-        self.symbols.push(position);
-    }
-
-    fn add_part(&mut self, part_number_to_parse: &str, start_position: Point) {
-        let length = u8::try_from(part_number_to_parse.len()).unwrap();
-        let value: u16 = part_number_to_parse.parse().unwrap();
-        self.parts.push(PartNumber {
-            start_position,
-            length,
-            value,
-        });
-    }
-
     fn part_numbers_next_to_symbol(&self) -> impl Iterator<Item = &PartNumber> {
         self.parts
             .iter()
             .filter(|part| part.is_next_to_any_symbols(&self.symbols))
     }
 
-    fn gear_ratio_per_symbol(&self) -> impl Iterator<Item = u32> + '_ {
+    /// Ratio of every gear, `None` when it overflows.
+    fn gear_ratio_per_symbol(&self) -> impl Iterator<Item = Option<u64>> + '_ {
         self.symbols
             .iter()
             .map(|symbol| {
                 self.parts
                     .iter()
                     .filter(|part| part.is_next_to(*symbol))
-                    .map(|part| u32::from(part.value))
+                    .map(|part| part.value)
                     .collect::<Vec<_>>()
             })
             .filter(|gears| gears.len() == 2)
-            .map(|gears| gears[0] * gears[1])
-    }
-}
-
-#[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
-struct Point {
-    x: u8,
-    y: u8,
-}
-
-impl Point {
-    fn new_line(&mut self) {
-        self.y += 1;
-        self.x = 0;
-    }
-
-    fn move_right(&mut self, count: u8) {
-        self.x += count;
-    }
-
-    fn next_to_with_additional_x_length(self, additional_x_lenght: u8, other: Self) -> bool {
-        let next_to_x = self.x.saturating_sub(1)..=self.x.saturating_add(additional_x_lenght);
-        let next_to_y = self.y.saturating_sub(1)..=self.y.saturating_add(1);
-        next_to_x.contains(&other.x) && next_to_y.contains(&other.y)
-    }
-}
-
-impl From<(u8, u8)> for Point {
-    fn from(point: (u8, u8)) -> Self {
-        let (x, y) = point;
-        Self { x, y }
+            .map(|gears| gears[0].checked_mul(gears[1]))
     }
 }
 
 #[derive(Debug, PartialEq, Eq)]
 struct PartNumber {
     start_position: Point,
-    length: u8,
-    value: u16,
+    length: usize,
+    value: u64,
 }
 
 impl PartNumber {
     fn is_next_to(&self, symbol: Point) -> bool {
-        self.start_position
-            .next_to_with_additional_x_length(self.length, symbol)
+        let start = self.start_position;
+        let next_to_x = start.x.saturating_sub(1)..=start.x.saturating_add(self.length);
+        next_to_x.contains(&symbol.x) && start.y.abs_diff(symbol.y) <= 1
     }
 
     fn is_next_to_any_symbols(&self, symbols: &[Point]) -> bool {
@@ -109,8 +89,8 @@ impl PartNumber {
     }
 }
 
-impl From<(u8, u8, u8, u16)> for PartNumber {
-    fn from(input: (u8, u8, u8, u16)) -> Self {
+impl From<(usize, usize, usize, u64)> for PartNumber {
+    fn from(input: (usize, usize, usize, u64)) -> Self {
         let (x, y, length, value) = input;
         Self {
             start_position: (x, y).into(),
@@ -121,41 +101,48 @@ impl From<(u8, u8, u8, u16)> for PartNumber {
 }
 
 mod parse {
-    use nom::IResult;
-
-    use super::{Input, Point};
-
-    pub fn parse(lines: &str) -> Option<Input> {
-        parse_internal(lines).map(|(_, input)| input).ok()
-    }
-
-    fn parse_internal(lines: &str) -> IResult<&str, Input> {
-        let (lines, (input, _)) = nom::multi::fold_many1(
-            nom::branch::alt((
-                nom::character::complete::digit1,
-                nom::bytes::complete::take(1_usize),
-            )),
-            || (Input::default(), Point::default()),
-            |(mut input, mut point), captured: &str| {
-                if captured == "\n" {
-                    point.new_line();
-                } else {
-                    match captured.chars().next().unwrap() {
-                        '0'..='9' => input.add_part(captured, point),
-                        '.' => (),
-                        _symbol => input.add_symbol(point),
+    use super::{AocError, Input, PartNumber, Point};
+    use crate::grid::Grid;
+
+    pub fn parse(lines: &str) -> Result<Input, AocError> {
+        let schematic = Grid::parse(lines, |c| {
+            if c.is_ascii_graphic() {
+                Ok(c)
+            } else {
+                Err("expected a digit, '.' or a symbol in the engine schematic")
+            }
+        })?;
+
+        let mut input = Input::default();
+        for ((y, row), line) in schematic.rows().enumerate().zip(lines.lines()) {
+            let mut x = 0;
+            while x < row.len() {
+                if !row[x].is_ascii_digit() {
+                    if row[x] != '.' {
+                        input.symbols.push(Point::new(x, y));
                     }
-                    point.move_right(u8::try_from(captured.len()).unwrap());
+                    x += 1;
+                    continue;
                 }
-                (input, point)
-            },
-        )(lines)?;
 
-        if !lines.is_empty() {
-            println!("Warning! Not all the input was eaten!\n{lines}");
+                let start = x;
+                while row.get(x).is_some_and(char::is_ascii_digit) {
+                    x += 1;
+                }
+                // the schematic is ASCII, so cells and bytes line up
+                let digits = &line[start..x];
+                let value = digits
+                    .parse()
+                    .map_err(|_| AocError::parse(lines, digits, "part number too large"))?;
+                input.parts.push(PartNumber {
+                    start_position: Point::new(start, y),
+                    length: x - start,
+                    value,
+                });
+            }
         }
 
-        Ok((lines, input))
+        Ok(input)
     }
 }
 
@@ -205,12 +192,25 @@ mod test {
     #[test]
     fn test_silver() {
         let parsed = parse::parse(INPUT).unwrap();
-        assert_eq!(silver(&parsed), 4361);
+        assert_eq!(silver(&parsed).unwrap(), 4361);
     }
 
     #[test]
     fn test_gold() {
         let parsed = parse::parse(INPUT).unwrap();
-        assert_eq!(gold(&parsed), 467_835);
+        assert_eq!(gold(&parsed).unwrap(), 467_835);
+    }
+
+    #[test]
+    fn test_wide_schematic() {
+        let mut lines = vec![".".repeat(400); 300];
+        lines[299].replace_range(297..300, "123");
+        lines[298].replace_range(300..301, "*");
+        lines[299].replace_range(301..304, "456");
+        let parsed = parse::parse(&lines.join("\n")).unwrap();
+
+        assert_eq!(parsed.symbols, [Point::new(300, 298)]);
+        assert_eq!(silver(&parsed).unwrap(), 123 + 456);
+        assert_eq!(gold(&parsed).unwrap(), 123 * 456);
     }
 }
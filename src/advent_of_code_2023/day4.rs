@@ -1,92 +1,121 @@
 use std::collections::{HashSet, VecDeque};
 
-use crate::{aoc_result::AOCResult, read_file::read_file};
+use crate::{
+    aoc_error::AocError,
+    checked,
+    solver::{PartOutput, Solver},
+};
 
-pub fn solve() -> AOCResult {
-    let input = read_file(2023, 4).expect("File input/2023/04.txt to exist");
-    let parsed: Vec<_> = parse::parse(&input).collect();
-    (silver(parsed.iter().copied()), gold(parsed.into_iter())).into()
-}
+pub struct Day4;
+
+impl Solver for Day4 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 4;
+
+    type Input = Vec<Line>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse::parse(input)
+    }
+
+    fn silver(input: &Self::Input) -> impl PartOutput {
+        silver(input.iter().copied())
+    }
 
-fn silver(lines: impl Iterator<Item = Line>) -> u32 {
-    lines.map(silver_line).sum()
+    fn gold(input: &Self::Input) -> impl PartOutput {
+        gold(input.iter().copied())
+    }
 }
 
-fn silver_line(line: Line) -> u32 {
-    let winning_count = u32::from(line.winning_numbers_count);
+fn silver(lines: impl Iterator<Item = Line>) -> Result<u64, AocError> {
+    let points = lines.map(silver_line).collect::<Result<Vec<_>, _>>()?;
+    checked::sum(points, "the sum of card points")
+}
 
-    match winning_count {
-        0 => 0,
-        _ => 2_u32.pow(winning_count - 1),
+fn silver_line(line: Line) -> Result<u64, AocError> {
+    match line.winning_numbers_count {
+        0 => Ok(0),
+        count => u32::try_from(count - 1)
+            .ok()
+            .and_then(|exponent| 2_u64.checked_pow(exponent))
+            .ok_or_else(|| AocError::overflow("the points of a card")),
     }
 }
 
-fn gold(lines: impl Iterator<Item = Line>) -> u32 {
+fn gold(lines: impl Iterator<Item = Line>) -> Result<u64, AocError> {
+    let overflow = || AocError::overflow("the number of scratchcards");
     let mut next_cards = VecDeque::new();
 
     lines
         .map(|line| line.winning_numbers_count)
-        .map(usize::from)
-        .fold(0, |acc, wins| {
-            let current_cards = next_cards.pop_front().unwrap_or(1);
+        .try_fold(0_u64, |acc, wins| {
+            let current_cards = next_cards.pop_front().unwrap_or(1_u64);
 
-            while next_cards.len() < wins {
-                next_cards.push_back(1);
+            if next_cards.len() < wins {
+                next_cards.resize(wins, 1);
             }
 
             for card in next_cards.range_mut(..wins) {
-                *card += current_cards;
+                *card = card.checked_add(current_cards).ok_or_else(overflow)?;
             }
 
-            acc + current_cards
+            acc.checked_add(current_cards).ok_or_else(overflow)
         })
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
-struct Line {
-    winning_numbers_count: u8,
+pub struct Line {
+    winning_numbers_count: usize,
 }
 
 #[derive(Debug, PartialEq)]
 struct RawParsedLine {
-    card: u8,
-    lucky: HashSet<u8>,
-    winning: HashSet<u8>,
+    card: u32,
+    lucky: HashSet<u32>,
+    winning: HashSet<u32>,
 }
 
 impl From<RawParsedLine> for Line {
     fn from(line: RawParsedLine) -> Self {
         Self {
-            winning_numbers_count: u8::try_from(line.lucky.intersection(&line.winning).count())
-                .expect("Winning number should be less than u8::max"),
+            winning_numbers_count: line.lucky.intersection(&line.winning).count(),
         }
     }
 }
 
 mod parse {
-    use nom::{bytes::complete::tag, character::complete::space1};
-
-    use super::{Line, RawParsedLine};
+    use nom::{bytes::complete::tag, character::complete::space1, error::context};
 
-    pub fn parse(input: &str) -> impl Iterator<Item = Line> + '_ {
-        input.lines().map(line)
-    }
+    use super::{AocError, Line, RawParsedLine};
+    use crate::diagnostic::{self, ParseResult};
 
-    fn line(input: &str) -> Line {
-        line_internal(input).unwrap().1
+    pub fn parse(input: &str) -> Result<Vec<Line>, AocError> {
+        diagnostic::parse_with(
+            input,
+            nom::multi::separated_list1(
+                nom::character::complete::newline,
+                nom::combinator::map(line, Line::from),
+            ),
+        )
     }
 
-    fn line_internal(input: &str) -> nom::IResult<&str, Line> {
-        let (input, card) = nom::sequence::delimited(
-            nom::sequence::tuple((tag("Card"), space1)),
-            nom::character::complete::u8,
-            nom::sequence::tuple((tag(":"), space1)),
+    fn line(input: &str) -> ParseResult<'_, RawParsedLine> {
+        let (input, card) = context(
+            "card header",
+            nom::sequence::delimited(
+                nom::sequence::tuple((tag("Card"), space1)),
+                nom::character::complete::u32,
+                nom::sequence::tuple((tag(":"), space1)),
+            ),
         )(input)?;
 
         let (input, (lucky, winning)) = nom::sequence::separated_pair(
-            space_separated_numbers,
-            nom::sequence::tuple((space1, tag("|"), space1)),
-            space_separated_numbers,
+            context("lucky numbers", space_separated_numbers),
+            context(
+                "'|' separator",
+                nom::sequence::tuple((space1, tag("|"), space1)),
+            ),
+            context("winning numbers", space_separated_numbers),
         )(input)?;
 
         Ok((
@@ -95,13 +124,12 @@ mod parse {
                 card,
                 lucky: lucky.iter().copied().collect(),
                 winning: winning.iter().copied().collect(),
-            }
-            .into(),
+            },
         ))
     }
 
-    fn space_separated_numbers(input: &str) -> nom::IResult<&str, Vec<u8>> {
-        nom::multi::separated_list1(space1, nom::character::complete::u8)(input)
+    fn space_separated_numbers(input: &str) -> ParseResult<'_, Vec<u32>> {
+        nom::multi::separated_list1(space1, nom::character::complete::u32)(input)
     }
 }
 
@@ -117,25 +145,34 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
     #[test]
     fn test_silver_line() {
-        let mut lines = parse(INPUT);
-        assert_eq!(silver_line(lines.next().unwrap()), 8);
-        assert_eq!(silver_line(lines.next().unwrap()), 2);
-        assert_eq!(silver_line(lines.next().unwrap()), 2);
-        assert_eq!(silver_line(lines.next().unwrap()), 1);
-        assert_eq!(silver_line(lines.next().unwrap()), 0);
-        assert_eq!(silver_line(lines.next().unwrap()), 0);
+        let mut lines = parse(INPUT).unwrap().into_iter();
+        assert_eq!(silver_line(lines.next().unwrap()).unwrap(), 8);
+        assert_eq!(silver_line(lines.next().unwrap()).unwrap(), 2);
+        assert_eq!(silver_line(lines.next().unwrap()).unwrap(), 2);
+        assert_eq!(silver_line(lines.next().unwrap()).unwrap(), 1);
+        assert_eq!(silver_line(lines.next().unwrap()).unwrap(), 0);
+        assert_eq!(silver_line(lines.next().unwrap()).unwrap(), 0);
         assert_eq!(lines.next(), None);
     }
 
+    #[test]
+    fn test_parse_trailing_text() {
+        let error = parse("Card 1: 1 2 | 1 4 xyz").unwrap_err().to_string();
+        assert!(
+            error.starts_with("Parse error at line 1, column 18: expected end of input, found ' '"),
+            "{error}"
+        );
+    }
+
     #[test]
     fn test_silver() {
-        let lines = parse(INPUT);
-        assert_eq!(silver(lines), 13);
+        let lines = parse(INPUT).unwrap().into_iter();
+        assert_eq!(silver(lines).unwrap(), 13);
     }
 
     #[test]
     fn test_gold() {
-        let lines = parse(INPUT);
-        assert_eq!(gold(lines), 30);
+        let lines = parse(INPUT).unwrap().into_iter();
+        assert_eq!(gold(lines).unwrap(), 30);
     }
 }
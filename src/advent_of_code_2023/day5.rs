@@ -1,51 +1,80 @@
-use std::ops::RangeInclusive;
+use std::fmt;
 
-use crate::{aoc_result::AOCResult, read_file::read_file};
+use crate::{
+    aoc_error::AocError,
+    diagnostic::{self, Diagnostic},
+    interval::{Interval, IntervalSet},
+    solver::{DumpOptions, PartOutput, Solver},
+};
 
-pub fn solve() -> AOCResult {
-    let input = read_file(2023, 5).expect("File input/2023/05.txt to exist");
-    let parsed = parse(&input);
-    (silver(&parsed), gold(&parsed)).into()
+pub struct Day5;
+
+impl Solver for Day5 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 5;
+
+    type Input = Input;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
+
+    fn silver(input: &Self::Input) -> impl PartOutput {
+        silver(input)
+    }
+
+    fn gold(input: &Self::Input) -> impl PartOutput {
+        gold(input)
+    }
+
+    fn dump(input: &Self::Input, _options: DumpOptions) -> Result<Option<String>, AocError> {
+        dump(input).map(Some)
+    }
 }
 
-fn silver(input: &Input) -> i64 {
+/// Category of the numbers listed on the `seeds:` line.
+const SEED: &str = "seed";
+const LOCATION: &str = "location";
+
+fn silver(input: &Input) -> Result<i64, AocError> {
+    let function = input.almanac.function(SEED, LOCATION)?;
     input
         .seeds
         .iter()
-        .map(|seed| {
-            input
-                .rules
-                .iter()
-                .fold(*seed, |origin, rule| rule.apply(origin))
-        })
+        .map(|seed| function.apply(*seed))
         .min()
-        .unwrap_or(i64::MAX)
+        .ok_or_else(|| AocError::solver("there is no seed"))
 }
 
-fn gold(input: &Input) -> i64 {
-    let (seeds, _) =
-        input
-            .seeds
-            .iter()
-            .fold((Vec::new(), None), |(mut acc, maybe_previous), cur| {
-                if let Some(previous) = maybe_previous {
-                    acc.push(previous..=(previous + cur));
-                    (acc, None)
-                } else {
-                    (acc, Some(*cur))
-                }
-            });
+/// Push the whole seed ranges through the composed maps, so the work depends on the number of
+/// ranges.
+fn gold(input: &Input) -> Result<i64, AocError> {
+    input
+        .almanac
+        .function(SEED, LOCATION)?
+        .apply_ranges(&seed_ranges(input)?)
+        .min()
+        .ok_or_else(|| AocError::solver("there is no seed range"))
+}
 
-    for tested_value in 1.. {
-        let input_key = gold_solve_part(&input.rules, tested_value);
-        if seeds.iter().any(|seed| seed.contains(&input_key)) {
-            return tested_value;
-        }
-    }
+fn dump(input: &Input) -> Result<String, AocError> {
+    let function = input.almanac.function(SEED, LOCATION)?;
+    Ok(format!("{SEED} to {LOCATION}:\n{function}"))
+}
 
-    i64::MAX
+fn seed_ranges(input: &Input) -> Result<IntervalSet<i64>, AocError> {
+    if let Some(unpaired) = &input.unpaired_seed {
+        return Err(AocError::Parse(unpaired.clone()));
+    }
+    input
+        .seeds
+        .chunks_exact(2)
+        .map(|pair| Interval::with_length(pair[0], pair[1]))
+        .collect::<Option<_>>()
+        .ok_or_else(|| AocError::overflow("the end of a seed range"))
 }
 
+#[cfg(test)]
 fn gold_solve_part(rules: &[Rule], terrain: i64) -> i64 {
     rules
         .iter()
@@ -53,23 +82,231 @@ fn gold_solve_part(rules: &[Rule], terrain: i64) -> i64 {
         .fold(terrain, |origin, rule| rule.rev_apply(origin))
 }
 
-struct Input {
+pub struct Input {
     seeds: Vec<i64>,
+    /// Where an odd `seeds:` line leaves its last start without a length, which only matters
+    /// once the seeds are read as ranges.
+    unpaired_seed: Option<Diagnostic>,
+    almanac: Almanac,
+}
+
+/// Chain of maps, from seeds to whatever category the last map leads to.
+pub struct Almanac {
+    /// `rules[i]` maps `categories[i]` to `categories[i + 1]`.
+    categories: Vec<String>,
     rules: Vec<Rule>,
 }
 
-struct Rule {
-    ranges: Vec<Translation>,
+impl Almanac {
+    fn position(&self, category: &str) -> Result<usize, AocError> {
+        self.categories
+            .iter()
+            .position(|known| known == category)
+            .ok_or_else(|| AocError::solver(format!("there is no {category} in the almanac")))
+    }
+
+    /// Every map from `from` to `to`, which must come after it in the chain, composed into one.
+    pub fn function(&self, from: &str, to: &str) -> Result<Piecewise, AocError> {
+        let (from_index, to_index) = (self.position(from)?, self.position(to)?);
+        if to_index < from_index {
+            return Err(AocError::solver(format!("{to} comes before {from}")));
+        }
+        self.rules[from_index..to_index]
+            .iter()
+            .try_fold(Piecewise::default(), |function, rule| {
+                function
+                    .then(&rule.into())
+                    .ok_or_else(|| AocError::overflow(format!("the map from {from} to {to}")))
+            })
+    }
+
+    /// Values of `to` corresponding to `value` of `from`: a single one going forward, every
+    /// value mapping to it going backward.
+    pub fn map_value(
+        &self,
+        value: i64,
+        from: &str,
+        to: &str,
+    ) -> Result<IntervalSet<i64>, AocError> {
+        let value = Interval::with_length(value, 1)
+            .ok_or_else(|| AocError::overflow("the end of the queried value"))?;
+        self.map_ranges(&value.into(), from, to)
+    }
+
+    /// Same as [`Self::map_value`], for whole ranges of values.
+    pub fn map_ranges(
+        &self,
+        ranges: &IntervalSet<i64>,
+        from: &str,
+        to: &str,
+    ) -> Result<IntervalSet<i64>, AocError> {
+        Ok(if self.position(from)? <= self.position(to)? {
+            self.function(from, to)?.apply_ranges(ranges)
+        } else {
+            self.function(to, from)?.preimage(ranges)
+        })
+    }
 }
 
-impl Rule {
-    fn apply(&self, origin: i64) -> i64 {
-        self.ranges
+/// Every value the pieces cover, up to `i64::MAX` excluded.
+const ALL: Interval<i64> = Interval::new(i64::MIN, i64::MAX);
+
+/// Function adding to a value the offset of the piece it falls in, the identity between pieces.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Piecewise {
+    /// Sorted disjoint domains with a non-zero offset, each image fitting in an `i64`.
+    pieces: Vec<(Interval<i64>, i64)>,
+}
+
+impl Piecewise {
+    /// Sort `pieces` and merge the adjacent ones, dropping the empty ones and the identity.
+    fn normalized(mut pieces: Vec<(Interval<i64>, i64)>) -> Self {
+        pieces.retain(|(domain, offset)| !domain.is_empty() && *offset != 0);
+        pieces.sort_unstable_by_key(|(domain, _)| domain.start());
+
+        let mut merged: Vec<(Interval<i64>, i64)> = Vec::with_capacity(pieces.len());
+        for (domain, offset) in pieces {
+            match merged.last_mut() {
+                Some((last, last_offset))
+                    if *last_offset == offset && last.end() == domain.start() =>
+                {
+                    *last = Interval::new(last.start(), domain.end());
+                }
+                _ => merged.push((domain, offset)),
+            }
+        }
+        Self { pieces: merged }
+    }
+
+    /// The pieces overlapping `within` and the identity between them, cut to `within`.
+    fn segments(&self, within: Interval<i64>) -> Vec<(Interval<i64>, i64)> {
+        let first = self
+            .pieces
+            .partition_point(|(domain, _)| domain.end() <= within.start());
+        let mut segments = Vec::new();
+        let mut cursor = within.start();
+        for (domain, offset) in self.pieces[first..]
             .iter()
-            .find_map(|translation| translation.apply_translation(origin))
-            .unwrap_or(origin)
+            .take_while(|(domain, _)| domain.start() < within.end())
+        {
+            let piece = domain.intersection(&within);
+            segments.push((Interval::new(cursor, piece.start()), 0));
+            segments.push((piece, *offset));
+            cursor = piece.end();
+        }
+        segments.push((Interval::new(cursor, within.end()), 0));
+        segments.retain(|(segment, _)| !segment.is_empty());
+        segments
+    }
+
+    /// Logarithmic in the number of pieces.
+    pub fn apply(&self, value: i64) -> i64 {
+        let index = self
+            .pieces
+            .partition_point(|(domain, _)| domain.end() <= value);
+        match self.pieces.get(index) {
+            Some((domain, offset)) if domain.contains(value) => value + offset,
+            _ => value,
+        }
+    }
+
+    pub fn apply_ranges(&self, values: &IntervalSet<i64>) -> IntervalSet<i64> {
+        values
+            .iter()
+            .flat_map(|interval| self.segments(*interval))
+            .map(|(domain, offset)| {
+                domain
+                    .shift(offset)
+                    .expect("the image of a piece to fit, as checked when building it")
+            })
+            .collect()
+    }
+
+    /// Every value mapped into `images`.
+    pub fn preimage(&self, images: &IntervalSet<i64>) -> IntervalSet<i64> {
+        self.segments(ALL)
+            .into_iter()
+            .flat_map(|(domain, offset)| {
+                let image = domain
+                    .shift(offset)
+                    .expect("the image of a piece to fit, as checked when building it");
+                images
+                    .intersection(&image.into())
+                    .shift(-offset)
+                    .expect("the domain to fit, being shifted back")
+                    .iter()
+                    .copied()
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// `self` then `next`, `None` when an offset or its opposite does not fit in an `i64`.
+    #[must_use]
+    pub fn then(&self, next: &Self) -> Option<Self> {
+        let mut pieces = Vec::new();
+        for (domain, offset) in self.segments(ALL) {
+            for (next_domain, next_offset) in next.segments(domain.shift(offset)?) {
+                let composed = offset.checked_add(next_offset)?;
+                composed.checked_neg()?;
+                pieces.push((next_domain.shift(-offset)?, composed));
+            }
+        }
+        Some(Self::normalized(pieces))
+    }
+
+    /// `None` when two values map to the same one.
+    #[must_use]
+    pub fn inverse(&self) -> Option<Self> {
+        let mut pieces = self
+            .segments(ALL)
+            .into_iter()
+            .map(|(domain, offset)| Some((domain.shift(offset)?, offset.checked_neg()?)))
+            .collect::<Option<Vec<_>>>()?;
+        pieces.sort_unstable_by_key(|(image, _)| image.start());
+        // disjoint images of the whole range cover it all again
+        pieces
+            .windows(2)
+            .all(|pair| pair[0].0.end() <= pair[1].0.start())
+            .then(|| Self::normalized(pieces))
+    }
+}
+
+impl From<&Rule> for Piecewise {
+    /// A translation only applies where no earlier one does.
+    fn from(rule: &Rule) -> Self {
+        let mut claimed = IntervalSet::new();
+        let mut pieces = Vec::new();
+        for translation in &rule.ranges {
+            let source = IntervalSet::from(translation.source);
+            pieces.extend(
+                source
+                    .difference(&claimed)
+                    .iter()
+                    .map(|domain| (*domain, translation.source_to_destination_offset)),
+            );
+            claimed = claimed.union(&source);
+        }
+        Self::normalized(pieces)
     }
+}
 
+impl fmt::Display for Piecewise {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (domain, offset) in &self.pieces {
+            let image = domain.shift(*offset).ok_or(fmt::Error)?;
+            writeln!(f, "{domain} -> {image} ({offset:+})")?;
+        }
+        write!(f, "identity elsewhere")
+    }
+}
+
+struct Rule {
+    ranges: Vec<Translation>,
+}
+
+impl Rule {
+    #[cfg(test)]
     fn rev_apply(&self, terrain: i64) -> i64 {
         self.ranges
             .iter()
@@ -85,93 +322,169 @@ impl From<Vec<Translation>> for Rule {
 }
 
 struct Translation {
-    source_range: RangeInclusive<i64>,
-    destination_range: RangeInclusive<i64>,
+    source: Interval<i64>,
     source_to_destination_offset: i64,
 }
 
 impl Translation {
-    fn apply_translation(&self, to_translate: i64) -> Option<i64> {
-        if self.source_range.contains(&to_translate) {
-            Some(to_translate + self.source_to_destination_offset)
-        } else {
-            None
-        }
+    /// `None` when the ranges do not fit in an `i64`.
+    fn new(destination_start: i64, source_start: i64, length: i64) -> Option<Self> {
+        let source = Interval::with_length(source_start, length)?;
+        let offset = destination_start.checked_sub(source_start)?;
+        // the destination must fit too, and the offset must be reversible
+        source.shift(offset)?;
+        offset.checked_neg()?;
+        Some(Self {
+            source,
+            source_to_destination_offset: offset,
+        })
     }
 
+    #[cfg(test)]
     fn rev_translation(&self, to_reverse: i64) -> Option<i64> {
-        if self.destination_range.contains(&to_reverse) {
-            Some(to_reverse - self.source_to_destination_offset)
-        } else {
-            None
-        }
+        let origin = to_reverse.checked_sub(self.source_to_destination_offset)?;
+        self.source.contains(origin).then_some(origin)
     }
 }
 
-fn parse(input: &str) -> Input {
-    parse::file(input).unwrap().1
+fn parse(input: &str) -> Result<Input, AocError> {
+    let (seeds, maps) = diagnostic::parse_with(input, parse::file)?;
+    let unpaired_seed = (!seeds.len().is_multiple_of(2)).then(|| {
+        let seeds_line = input.lines().next().unwrap_or_default();
+        let last = seeds_line.split_whitespace().last().unwrap_or(seeds_line);
+        Diagnostic::new(input, last, "the last seed range has no length")
+    });
+    Ok(Input {
+        seeds,
+        unpaired_seed,
+        almanac: parse::almanac(input, maps)?,
+    })
 }
 
 mod parse {
-    use nom::IResult;
+    use nom::error::context;
 
-    use super::{Input, Rule, Translation};
+    use super::{Almanac, AocError, Rule, Translation, SEED};
+    use crate::diagnostic::ParseResult;
 
-    pub fn file(input: &str) -> IResult<&str, Input> {
-        let (input, _) = nom::bytes::complete::tag("seeds: ")(input)?;
-        let (input, seeds) = nom::multi::separated_list1(
-            nom::character::complete::space1,
-            nom::character::complete::i64,
-        )(input)?;
-        let (input, rules) = nom::multi::fold_many1(
+    /// Source and destination categories of a map, as found in the input, and its rule.
+    pub type Map<'a> = (&'a str, &'a str, Rule);
+
+    pub fn file(input: &str) -> ParseResult<'_, (Vec<i64>, Vec<Map<'_>>)> {
+        let (input, seeds) = context(
+            "seeds list",
             nom::sequence::preceded(
-                nom::sequence::tuple((
-                    nom::multi::many1(nom::character::complete::newline),
-                    nom::character::complete::alpha1,
-                    nom::bytes::complete::tag("-to-"),
-                    nom::character::complete::alpha1,
-                    nom::bytes::complete::tag(" map:"),
-                )),
-                nom::multi::fold_many1(
-                    nom::sequence::preceded(
-                        nom::character::complete::newline,
-                        nom::multi::separated_list1(
-                            nom::bytes::complete::tag(" "),
-                            nom::character::complete::i64,
+                nom::bytes::complete::tag("seeds: "),
+                nom::multi::separated_list1(
+                    nom::character::complete::space1,
+                    nom::character::complete::i64,
+                ),
+            ),
+        )(input)?;
+        let (input, maps) = context(
+            "almanac maps",
+            nom::multi::fold_many1(
+                nom::sequence::pair(
+                    context(
+                        "map header",
+                        nom::sequence::preceded(
+                            nom::sequence::pair(
+                                nom::multi::many1(nom::character::complete::newline),
+                                nom::combinator::not(nom::combinator::eof),
+                            ),
+                            nom::combinator::cut(nom::sequence::tuple((
+                                nom::character::complete::alpha1,
+                                nom::bytes::complete::tag("-to-"),
+                                nom::character::complete::alpha1,
+                                nom::bytes::complete::tag(" map:"),
+                            ))),
                         ),
                     ),
-                    Vec::new,
-                    |mut acc: Vec<Translation>, input: Vec<i64>| {
-                        let destination_range_start = input[0];
-                        let source_range_start = input[1];
-                        let range_length = input[2];
-
-                        acc.push(Translation {
-                            source_range: source_range_start..=source_range_start + range_length,
-                            destination_range: destination_range_start
-                                ..=destination_range_start + range_length,
-                            source_to_destination_offset: destination_range_start
-                                - source_range_start,
-                        });
-
-                        acc
-                    },
+                    nom::multi::fold_many1(
+                        nom::sequence::preceded(
+                            nom::sequence::terminated(
+                                nom::character::complete::newline,
+                                nom::combinator::peek(nom::character::complete::digit1),
+                            ),
+                            nom::combinator::cut(context(
+                                "map entry",
+                                nom::combinator::map_opt(
+                                    nom::sequence::tuple((
+                                        nom::character::complete::i64,
+                                        nom::sequence::preceded(
+                                            nom::bytes::complete::tag(" "),
+                                            nom::character::complete::i64,
+                                        ),
+                                        nom::sequence::preceded(
+                                            nom::bytes::complete::tag(" "),
+                                            nom::character::complete::i64,
+                                        ),
+                                    )),
+                                    |(destination_start, source_start, length)| {
+                                        Translation::new(destination_start, source_start, length)
+                                    },
+                                ),
+                            )),
+                        ),
+                        Vec::new,
+                        |mut acc: Vec<Translation>, translation| {
+                            acc.push(translation);
+                            acc
+                        },
+                    ),
                 ),
+                Vec::new,
+                |mut acc: Vec<Map>, ((source, _, destination, _), translations)| {
+                    acc.push((source, destination, translations.into()));
+                    acc
+                },
             ),
-            Vec::new,
-            |mut acc: Vec<Rule>, item| {
-                acc.push(item.into());
-                acc
-            },
         )(input)?;
 
-        Ok((input, Input { seeds, rules }))
+        Ok((input, (seeds, maps)))
+    }
+
+    /// Chain the maps from seeds on, each one starting where the previous one ends.
+    pub fn almanac(input: &str, maps: Vec<Map<'_>>) -> Result<Almanac, AocError> {
+        let mut categories = vec![SEED.to_owned()];
+        for (index, (source, destination, _)) in maps.iter().enumerate() {
+            let expected = categories.last().map_or(SEED, String::as_str);
+            if *source != expected {
+                let message = if maps[index + 1..]
+                    .iter()
+                    .any(|(later, ..)| *later == expected)
+                {
+                    format!("maps out of order, the map from {expected} must come first")
+                } else {
+                    format!("missing the map from {expected}")
+                };
+                return Err(AocError::parse(input, source, message));
+            }
+            if categories.iter().any(|known| known == destination) {
+                return Err(AocError::parse(
+                    input,
+                    destination,
+                    format!("{destination} is already mapped, the maps go round in circles"),
+                ));
+            }
+            categories.push((*destination).to_owned());
+        }
+
+        Ok(Almanac {
+            categories,
+            rules: maps.into_iter().map(|(_, _, rule)| rule).collect(),
+        })
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::{gold, parse, silver};
+    use super::{gold, gold_solve_part, parse, seed_ranges, silver, Day5, Input, Piecewise};
+    use crate::{
+        aoc_result::{AOCResult, Answer, PartResult},
+        interval::{Interval, IntervalSet},
+        solver::{DumpOptions, Parts, Puzzle},
+    };
 
     const INPUT: &str = "seeds: 79 14 55 13
 
@@ -206,15 +519,239 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
+    #[test]
+    fn test_parse_crlf() {
+        let error = parse(&INPUT.replace('\n', "\r\n"))
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(
+            error.starts_with("Parse error at line 1, column 19: expected almanac maps"),
+            "{error}"
+        );
+        assert!(error.contains("CRLF"));
+    }
+
     #[test]
     fn test_silver() {
-        let parsed = parse(INPUT);
-        assert_eq!(silver(&parsed), 35);
+        let parsed = parse(INPUT).unwrap();
+        assert_eq!(silver(&parsed).unwrap(), 35);
+    }
+
+    #[test]
+    fn test_solve_silver_only() {
+        assert_eq!(
+            Day5.solve(INPUT, Parts::Silver).unwrap(),
+            AOCResult::new(PartResult::Solved(Answer::Signed(35)), PartResult::Skipped)
+        );
+        assert_eq!(
+            Day5.solve(INPUT, Parts::Both).unwrap(),
+            (35_i64, 46_i64).into()
+        );
+    }
+
+    /// Former solution: search every location from 0 until one maps back into a seed range.
+    fn gold_brute_force(input: &Input) -> i64 {
+        let seeds = seed_ranges(input).unwrap();
+        (0..)
+            .find(|location| seeds.contains(gold_solve_part(&input.almanac.rules, *location)))
+            .unwrap()
     }
 
     #[test]
     fn test_gold() {
-        let parsed = parse(INPUT);
-        assert_eq!(gold(&parsed), 46);
+        let parsed = parse(INPUT).unwrap();
+        assert_eq!(gold(&parsed).unwrap(), 46);
+        assert_eq!(gold_brute_force(&parsed), 46);
+    }
+
+    #[test]
+    fn test_gold_against_brute_force() {
+        const SIZE: i64 = 100;
+        let mut state = 0x853c_49e6_748f_ea9b_u64;
+        let mut random = |below: i64| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 33) as i64 % below
+        };
+
+        for _ in 0..200 {
+            let seeds: Vec<_> = (0..4)
+                .map(|_| {
+                    let start = random(SIZE - 10);
+                    format!("{start} {}", 1 + random(10))
+                })
+                .collect();
+
+            // each map moves blocks of [0, SIZE) around, so that it can be reversed
+            let maps: Vec<_> = (0..3)
+                .map(|map| {
+                    let mut cuts: Vec<_> = (0..5).map(|_| random(SIZE)).collect();
+                    cuts.extend([0, SIZE]);
+                    cuts.sort_unstable();
+                    cuts.dedup();
+                    let mut blocks: Vec<_> = cuts.windows(2).map(|w| (w[0], w[1] - w[0])).collect();
+                    for index in (1..blocks.len()).rev() {
+                        blocks.swap(index, random(index as i64 + 1) as usize);
+                    }
+
+                    let mut destination = 0;
+                    let entries: Vec<_> = blocks
+                        .iter()
+                        .map(|(source, length)| {
+                            let entry = format!("{destination} {source} {length}");
+                            destination += length;
+                            entry
+                        })
+                        .collect();
+                    let name = |index: usize| ["seed", "soil", "water", "location"][index];
+                    format!(
+                        "{}-to-{} map:\n{}",
+                        name(map),
+                        name(map + 1),
+                        entries.join("\n")
+                    )
+                })
+                .collect();
+
+            let input = format!("seeds: {}\n\n{}", seeds.join(" "), maps.join("\n\n"));
+            let parsed = parse(&input).unwrap();
+            assert_eq!(gold(&parsed).unwrap(), gold_brute_force(&parsed), "{input}");
+
+            // the maps are bijections, so each location comes from exactly one seed
+            let location = random(SIZE);
+            let seeds = parsed
+                .almanac
+                .map_value(location, "location", "seed")
+                .unwrap();
+            let seed = gold_solve_part(&parsed.almanac.rules, location);
+            assert_eq!(seeds, Interval::new(seed, seed + 1).into(), "{input}");
+            let function = parsed.almanac.function("seed", "location").unwrap();
+            assert_eq!(function.inverse().unwrap().apply(location), seed, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_gold_unpaired_seed() {
+        // the caret lands on the last seed, whatever separates them
+        for seeds in ["10 5 20", "10\t5\t20"] {
+            let parsed =
+                parse(&format!("seeds: {seeds}\n\nseed-to-location map:\n0 0 100")).unwrap();
+            assert_eq!(silver(&parsed).unwrap(), 5);
+            let error = gold(&parsed).unwrap_err().to_string();
+            assert!(
+                error.starts_with(
+                    "Parse error at line 1, column 13: the last seed range has no length"
+                ),
+                "{error}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_gold_far_location() {
+        let parsed = parse("seeds: 10 5\n\nseed-to-location map:\n1000000000000000 0 100").unwrap();
+        assert_eq!(gold(&parsed).unwrap(), 1_000_000_000_000_010);
+    }
+
+    #[test]
+    fn test_map_between_categories() {
+        let almanac = parse(INPUT).unwrap().almanac;
+        let value = |value: i64| IntervalSet::from(Interval::new(value, value + 1));
+        assert_eq!(almanac.map_value(79, "seed", "soil").unwrap(), value(81));
+        assert_eq!(almanac.map_value(14, "soil", "water").unwrap(), value(49));
+        assert_eq!(
+            almanac.map_value(82, "seed", "location").unwrap(),
+            value(46)
+        );
+        assert_eq!(almanac.map_value(81, "soil", "seed").unwrap(), value(79));
+        // 50 is moved away, and only 98 lands on it
+        assert_eq!(almanac.map_value(50, "soil", "seed").unwrap(), value(98));
+        assert_eq!(almanac.map_value(10, "soil", "seed").unwrap(), value(10));
+        assert!(almanac
+            .map_value(46, "location", "seed")
+            .unwrap()
+            .contains(82));
+
+        let error = almanac.map_value(1, "seed", "sun").unwrap_err();
+        assert!(error
+            .to_string()
+            .ends_with("there is no sun in the almanac"));
+    }
+
+    #[test]
+    fn test_piecewise() {
+        let almanac = parse(INPUT).unwrap().almanac;
+        let soil = almanac.function("seed", "soil").unwrap();
+        assert_eq!(
+            soil.to_string(),
+            "[50, 98) -> [52, 100) (+2)\n[98, 100) -> [50, 52) (-48)\nidentity elsewhere"
+        );
+        assert_eq!(
+            soil.inverse().unwrap().to_string(),
+            "[50, 52) -> [98, 100) (+48)\n[52, 100) -> [50, 98) (-2)\nidentity elsewhere"
+        );
+        assert_eq!(soil.inverse().unwrap().inverse().unwrap(), soil);
+
+        let location = almanac.function("seed", "location").unwrap();
+        for (seed, expected) in [(79, 82), (14, 43), (55, 86), (13, 35)] {
+            assert_eq!(location.apply(seed), expected);
+        }
+        let composed = almanac.function("seed", "fertilizer").unwrap();
+        let stepped = almanac.function("soil", "fertilizer").unwrap();
+        for seed in -5..120 {
+            assert_eq!(composed.apply(seed), stepped.apply(soil.apply(seed)));
+        }
+
+        // 10 and 0 both end up on 0
+        let almanac = parse("seeds: 1\n\nseed-to-soil map:\n0 10 5")
+            .unwrap()
+            .almanac;
+        assert_eq!(almanac.function("seed", "soil").unwrap().inverse(), None);
+        assert!(almanac.function("soil", "seed").is_err());
+    }
+
+    #[test]
+    fn test_dump() {
+        let dump = Day5.dump(INPUT, DumpOptions::default()).unwrap().unwrap();
+        assert!(dump.starts_with("seed to location:\n"), "{dump}");
+        assert!(dump.ends_with("identity elsewhere"), "{dump}");
+    }
+
+    #[test]
+    fn test_composed_overflow() {
+        let first = Piecewise::normalized(vec![(Interval::new(-20, -10), i64::MAX - 10)]);
+        let image = Interval::new(i64::MAX - 30, i64::MAX - 20);
+        let almost = Piecewise::normalized(vec![(image, 5)]);
+        assert_eq!(first.then(&almost).unwrap().apply(-20), i64::MAX - 25);
+        let too_far = Piecewise::normalized(vec![(image, 15)]);
+        assert_eq!(first.then(&too_far), None);
+    }
+
+    #[test]
+    fn test_broken_chain() {
+        let error = |input: &str| parse(input).err().unwrap().to_string();
+        let missing = error("seeds: 1\n\nseed-to-soil map:\n1 2 3\n\nwater-to-light map:\n1 2 3");
+        assert!(
+            missing.starts_with("Parse error at line 6, column 1: missing the map from soil"),
+            "{missing}"
+        );
+
+        let swapped = INPUT.replacen("seed-to-soil", "XXX", 1);
+        let swapped = swapped
+            .replacen("soil-to-fertilizer", "seed-to-soil", 1)
+            .replacen("XXX", "soil-to-fertilizer", 1);
+        let out_of_order = error(&swapped);
+        assert!(
+            out_of_order.contains("line 3, column 1: maps out of order, the map from seed"),
+            "{out_of_order}"
+        );
+
+        let circle = error("seeds: 1\n\nseed-to-soil map:\n1 2 3\n\nsoil-to-seed map:\n1 2 3");
+        assert!(
+            circle.contains("line 6, column 9: seed is already mapped"),
+            "{circle}"
+        );
     }
 }
@@ -1,105 +1,156 @@
-use crate::{aoc_result::AOCResult, read_file::read_file};
+use crate::{
+    aoc_error::AocError,
+    checked,
+    solver::{PartOutput, Solver},
+};
 
-pub fn solve() -> AOCResult {
-    let input = read_file(2023, 6).expect("File input/2023/06.txt to exist");
-    let (silver_input, gold) = parse::parse(&input);
-    (silver(&silver_input), nbr_possibility_beat_record(&gold)).into()
+pub struct Day6;
+
+impl Solver for Day6 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 6;
+
+    type Input = (Vec<Race>, Option<Race>);
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse::parse(input)
+    }
+
+    fn silver((races, _): &Self::Input) -> impl PartOutput {
+        silver(races)
+    }
+
+    fn gold((_, race): &Self::Input) -> impl PartOutput {
+        race.as_ref()
+            .map(nbr_possibility_beat_record)
+            .ok_or_else(|| AocError::overflow("the concatenated race"))
+    }
 }
 
-fn silver(races: &[Race]) -> u64 {
-    races.iter().map(nbr_possibility_beat_record).product()
+fn silver(races: &[Race]) -> Result<u64, AocError> {
+    checked::product(
+        races.iter().map(nbr_possibility_beat_record),
+        "the product of the ways to win",
+    )
 }
 
-#[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
 fn nbr_possibility_beat_record(race: &Race) -> u64 {
-    let time = f64::from(race.time);
-
-    // don't really know how to avoid precision loss, but should not impact regular aoc input
-    #[allow(clippy::cast_precision_loss)]
-    let distance_record = (race.distance_record + 1) as f64;
-
-    let root = 4_f64.mul_add(-distance_record, time.powi(2)).sqrt();
-    let lower = ((time - root) / 2.).ceil();
-    let upper = ((time + root) / 2.).floor();
+    let time = race.time;
+    let beats_record =
+        |hold: u64| u128::from(hold) * u128::from(time - hold) > u128::from(race.distance_record);
+
+    // the distance grows until half of the time, then mirrors
+    let half = time / 2;
+    if !beats_record(half) {
+        return 0;
+    }
+    let (mut lower, mut upper) = (0, half);
+    while lower < upper {
+        let middle = lower + (upper - lower) / 2;
+        if beats_record(middle) {
+            upper = middle;
+        } else {
+            lower = middle + 1;
+        }
+    }
 
-    (upper as u64) - (lower as u64) + 1
+    time - 2 * lower + 1
 }
 
 #[derive(Debug, PartialEq, Eq)]
-struct Race {
-    time: u32,
+pub struct Race {
+    time: u64,
     distance_record: u64,
 }
 
 mod parse {
-    use nom::IResult;
+    use nom::error::context;
+
+    use super::{multiplier, AocError, Race};
+    use crate::diagnostic::{self, ParseResult};
+
+    pub fn parse(input: &str) -> Result<(Vec<Race>, Option<Race>), AocError> {
+        let (times, distances) = diagnostic::parse_with(input, parse_internal)?;
+
+        if times.len() != distances.len() {
+            return Err(AocError::parse(
+                input,
+                input.lines().nth(1).unwrap_or(input),
+                format!("{} times but {} distances", times.len(), distances.len()),
+            ));
+        }
+
+        let silver_races: Vec<_> = times
+            .into_iter()
+            .zip(distances)
+            .map(|(time, distance_record)| Race {
+                time,
+                distance_record,
+            })
+            .collect();
+
+        // the race read with the spaces removed, unless it does not fit
+        let gold_race = silver_races.iter().try_fold(
+            Race {
+                time: 0,
+                distance_record: 0,
+            },
+            |gold_race, race| {
+                Some(Race {
+                    time: concatenate(gold_race.time, race.time)?,
+                    distance_record: concatenate(gold_race.distance_record, race.distance_record)?,
+                })
+            },
+        );
 
-    use super::{multiplier, Race};
+        Ok((silver_races, gold_race))
+    }
 
-    pub fn parse(input: &str) -> (Vec<Race>, Race) {
-        parse_internal(input).unwrap().1
+    fn concatenate(left: u64, right: u64) -> Option<u64> {
+        left.checked_mul(multiplier(right)?)?.checked_add(right)
     }
 
-    fn parse_internal(input: &str) -> IResult<&str, (Vec<Race>, Race)> {
-        let (input, times) = nom::sequence::preceded(
-            nom::sequence::pair(
-                nom::bytes::complete::tag("Time:"),
-                nom::character::complete::space1,
-            ),
-            nom::multi::separated_list1(
-                nom::character::complete::space1,
-                nom::character::complete::u32,
+    fn parse_internal(input: &str) -> ParseResult<'_, (Vec<u64>, Vec<u64>)> {
+        let (input, times) = context(
+            "times line",
+            nom::sequence::preceded(
+                nom::sequence::pair(
+                    nom::bytes::complete::tag("Time:"),
+                    nom::character::complete::space1,
+                ),
+                nom::multi::separated_list1(
+                    nom::character::complete::space1,
+                    nom::character::complete::u64,
+                ),
             ),
         )(input)?;
 
-        let mut times = times.into_iter();
-        let (input, silver_races) = nom::sequence::preceded(
-            nom::bytes::complete::tag("\nDistance:"),
-            nom::multi::fold_many1(
-                nom::sequence::preceded(
+        let (input, distances) = context(
+            "distances line",
+            nom::sequence::preceded(
+                nom::bytes::complete::tag("\nDistance:"),
+                nom::multi::many1(nom::sequence::preceded(
                     nom::character::complete::space1,
                     nom::character::complete::u64,
-                ),
-                Vec::new,
-                |mut acc, distance| {
-                    let time = times.next().unwrap();
-                    acc.push(Race {
-                        time,
-                        distance_record: distance,
-                    });
-                    acc
-                },
+                )),
             ),
         )(input)?;
 
-        let gold_race = silver_races.iter().fold(
-            Race {
-                time: 0,
-                distance_record: 0,
-            },
-            |mut gold_race, race| {
-                gold_race.time = gold_race.time * 100 + race.time;
-
-                let multiplier = multiplier(race.distance_record);
-
-                gold_race.distance_record =
-                    gold_race.distance_record * multiplier + race.distance_record;
-
-                gold_race
-            },
-        );
-
-        Ok((input, (silver_races, gold_race)))
+        Ok((input, (times, distances)))
     }
 }
 
-const fn multiplier(mut input: u64) -> u64 {
+/// Power of ten shifting a number left of `input`, `None` when it overflows.
+const fn multiplier(mut input: u64) -> Option<u64> {
     let mut res = 10u64;
     while input >= 10 {
         input /= 10;
-        res *= 10;
+        res = match res.checked_mul(10) {
+            Some(res) => res,
+            None => return None,
+        };
     }
-    res
+    Some(res)
 }
 
 #[cfg(test)]
@@ -111,7 +162,7 @@ Distance:  9  40  200";
 
     #[test]
     fn test_parse() {
-        let (silver, gold) = parse(INPUT);
+        let (silver, gold) = parse(INPUT).unwrap();
 
         assert_eq!(
             silver,
@@ -132,22 +183,22 @@ Distance:  9  40  200";
         );
         assert_eq!(
             gold,
-            Race {
+            Some(Race {
                 time: 71530,
                 distance_record: 940_200
-            },
+            }),
         );
     }
 
     #[test]
     fn test_silver() {
-        let (parsed, gold_parsed) = parse(INPUT);
-        assert_eq!(nbr_possibility_beat_record(&gold_parsed), 71503);
+        let (parsed, gold_parsed) = parse(INPUT).unwrap();
+        assert_eq!(nbr_possibility_beat_record(&gold_parsed.unwrap()), 71503);
         let mut silver_iter = parsed.iter().map(nbr_possibility_beat_record);
         assert_eq!(silver_iter.next(), Some(4));
         assert_eq!(silver_iter.next(), Some(8));
         assert_eq!(silver_iter.next(), Some(9));
         assert_eq!(silver_iter.next(), None);
-        assert_eq!(silver(&parsed), 288);
+        assert_eq!(silver(&parsed).unwrap(), 288);
     }
 }
@@ -1,32 +1,56 @@
 use itertools::Itertools;
 use std::{cmp::Ordering, collections::HashMap};
 
-use crate::{aoc_result::AOCResult, read_file::read_file};
+use crate::{
+    aoc_error::AocError,
+    checked,
+    solver::{PartOutput, Solver},
+};
 
-pub fn solve() -> AOCResult {
-    let input = read_file(2023, 7).expect("File input/2023/07.txt to exist");
-    let mut parsed = parse::parse(&input);
-    (silver(&mut parsed), gold(&mut parsed)).into()
+pub struct Day7;
+
+impl Solver for Day7 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 7;
+
+    type Input = Vec<Line>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse::parse(input)
+    }
+
+    fn silver(input: &Self::Input) -> impl PartOutput {
+        silver(input)
+    }
+
+    fn gold(input: &Self::Input) -> impl PartOutput {
+        gold(input)
+    }
 }
 
-fn silver(lines: &mut [Line]) -> u32 {
+fn silver(lines: &[Line]) -> Result<u64, AocError> {
+    let mut lines: Vec<_> = lines.iter().collect();
     lines.sort_unstable_by(|line, other| Hand::cmp(&line.hand.0, &other.hand.0));
 
-    lines
-        .iter()
-        .zip(1..)
-        .map(|(line, index)| line.bid * index)
-        .sum()
+    total_winnings(&lines)
 }
 
-fn gold(lines: &mut [Line]) -> u32 {
+fn gold(lines: &[Line]) -> Result<u64, AocError> {
+    let mut lines: Vec<_> = lines.iter().collect();
     lines.sort_unstable_by(|line, other| Hand::cmp(&line.hand.1, &other.hand.1));
 
-    lines
+    total_winnings(&lines)
+}
+
+/// Sum of every bid times its rank, `ranked` going from the weakest hand to the strongest.
+fn total_winnings(ranked: &[&Line]) -> Result<u64, AocError> {
+    let winnings = ranked
         .iter()
         .zip(1..)
-        .map(|(line, index)| line.bid * index)
-        .sum()
+        .map(|(line, rank)| line.bid.checked_mul(rank))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| AocError::overflow("the winnings of a hand"))?;
+    checked::sum(winnings, "the total winnings")
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -99,13 +123,12 @@ enum HandType {
     HighCard,
 }
 
-impl<T> From<T> for HandType
-where
-    T: Iterator<Item = Card>,
-{
-    fn from(cards: T) -> Self {
+impl TryFrom<&[Card]> for HandType {
+    type Error = String;
+
+    fn try_from(cards: &[Card]) -> Result<Self, Self::Error> {
         let mut map: HashMap<Card, u8> = HashMap::new();
-        for card in cards {
+        for card in cards.iter().copied() {
             map.entry(card).and_modify(|n| *n += 1).or_insert(1);
         }
 
@@ -115,14 +138,14 @@ where
         let second_best = iter.next().unwrap_or(0);
 
         match (best, second_best) {
-            (5, 0) => Self::FiveOfAKind,
-            (4, 1) => Self::FourOfAKind,
-            (3, 2) => Self::FullHouse,
-            (3, 1) => Self::ThreeOfAKind,
-            (2, 2) => Self::TwoPair,
-            (2, 1) => Self::OnePair,
-            (1, 1) => Self::HighCard,
-            _ => panic!("Unexpected tuple: ({best}, {second_best})"),
+            (5, 0) => Ok(Self::FiveOfAKind),
+            (4, 1) => Ok(Self::FourOfAKind),
+            (3, 2) => Ok(Self::FullHouse),
+            (3, 1) => Ok(Self::ThreeOfAKind),
+            (2, 2) => Ok(Self::TwoPair),
+            (2, 1) => Ok(Self::OnePair),
+            (1, 1) => Ok(Self::HighCard),
+            _ => Err(format!("{} cards do not make a hand", cards.len())),
         }
     }
 }
@@ -157,12 +180,14 @@ struct Hand {
     cards: Vec<Card>,
 }
 
-impl From<Vec<Card>> for Hand {
-    fn from(cards: Vec<Card>) -> Self {
-        Self {
-            hand_type: HandType::from(cards.iter().copied()),
+impl TryFrom<Vec<Card>> for Hand {
+    type Error = String;
+
+    fn try_from(cards: Vec<Card>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            hand_type: HandType::try_from(cards.as_slice())?,
             cards,
-        }
+        })
     }
 }
 
@@ -173,57 +198,77 @@ impl PartialOrd for Hand {
 }
 impl Ord for Hand {
     fn cmp(&self, other: &Self) -> Ordering {
-        match self.hand_type.cmp(&other.hand_type) {
-            Ordering::Equal => match self.cards.cmp(&other.cards) {
-                Ordering::Equal => panic!("Two Hands are perfectly equal, should not append!"),
-                ord => ord,
-            },
-            ord => ord,
-        }
+        self.hand_type
+            .cmp(&other.hand_type)
+            .then_with(|| self.cards.cmp(&other.cards))
     }
 }
 
 #[derive(Debug)]
-struct Line {
+pub struct Line {
     hand: (Hand, Hand),
-    bid: u32,
+    bid: u64,
 }
 
 mod parse {
-    use nom::IResult;
+    use std::collections::HashSet;
+
+    use nom::error::context;
+
+    use super::{AocError, Card, Hand, Line};
+    use crate::diagnostic::{self, ParseResult};
+
+    const CARDS: usize = 5;
 
-    use super::{Card, Line};
-    pub fn parse(input: &str) -> Vec<Line> {
-        internal(input).unwrap().1
+    pub fn parse(input: &str) -> Result<Vec<Line>, AocError> {
+        let lines = diagnostic::parse_with(input, internal)?;
+
+        // identical hands would have no rank between them
+        let mut dealt = HashSet::new();
+        for (cards, _) in &lines {
+            if !dealt.insert(*cards) {
+                return Err(AocError::parse(
+                    input,
+                    cards,
+                    format!("hand {cards} is dealt twice"),
+                ));
+            }
+        }
+        Ok(lines.into_iter().map(|(_, line)| line).collect())
     }
 
-    fn internal(input: &str) -> IResult<&str, Vec<Line>> {
+    fn internal(input: &str) -> ParseResult<'_, Vec<(&str, Line)>> {
         nom::multi::separated_list1(nom::character::complete::newline, line)(input)
     }
 
-    fn line(input: &str) -> IResult<&str, Line> {
-        let (input, (cards, bid)) = nom::sequence::separated_pair(
-            nom::character::complete::alphanumeric1,
+    /// A line along with its cards as written, to locate errors.
+    fn line(input: &str) -> ParseResult<'_, (&str, Line)> {
+        let (input, ((cards, hand), bid)) = nom::sequence::separated_pair(
+            context(
+                "hand of five cards",
+                nom::combinator::consumed(nom::combinator::map_res(
+                    nom::multi::count(
+                        nom::combinator::map_res(nom::character::complete::anychar, |card| {
+                            u8::try_from(card)
+                                .map_err(|e| e.to_string())
+                                .and_then(Card::try_from)
+                        }),
+                        CARDS,
+                    ),
+                    hands,
+                )),
+            ),
             nom::bytes::complete::tag(" "),
-            nom::character::complete::u32,
+            context("bid", nom::character::complete::u64),
         )(input)?;
 
-        let silver_cards = cards
-            .as_bytes()
-            .iter()
-            .map(|card| Card::try_from(*card))
-            .map(Result::unwrap)
-            .collect::<Vec<_>>();
-
-        let gold_cards: Vec<_> = silver_cards.iter().map(|card| card.into_gold()).collect();
-
-        Ok((
-            input,
-            Line {
-                hand: (silver_cards.into(), gold_cards.into()),
-                bid,
-            },
-        ))
+        Ok((input, (cards, Line { hand, bid })))
+    }
+
+    /// The cards read as a hand, then with jacks as jokers.
+    fn hands(cards: Vec<Card>) -> Result<(Hand, Hand), String> {
+        let gold_cards: Vec<_> = cards.iter().map(|card| card.into_gold()).collect();
+        Ok((Hand::try_from(cards)?, Hand::try_from(gold_cards)?))
     }
 }
 
@@ -239,7 +284,10 @@ QQQJA 483";
 
     #[test]
     fn test_parse() {
-        let mut parsed = parse(INPUT).into_iter().map(|line| line.hand.0.hand_type);
+        let mut parsed = parse(INPUT)
+            .unwrap()
+            .into_iter()
+            .map(|line| line.hand.0.hand_type);
         assert_eq!(parsed.next(), Some(HandType::OnePair));
         assert_eq!(parsed.next(), Some(HandType::ThreeOfAKind));
         assert_eq!(parsed.next(), Some(HandType::TwoPair));
@@ -248,14 +296,29 @@ QQQJA 483";
         assert_eq!(parsed.next(), None);
     }
 
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("22 5").unwrap_err().to_string();
+        assert!(
+            error.starts_with("Parse error at line 1, column 3: expected hand of five cards"),
+            "{error}"
+        );
+
+        let error = parse("AAAAA 5\nAAAAA 6").unwrap_err().to_string();
+        assert!(
+            error.starts_with("Parse error at line 2, column 1: hand AAAAA is dealt twice"),
+            "{error}"
+        );
+    }
+
     #[test]
     fn test_silver() {
-        let mut parsed = parse(INPUT);
-        assert_eq!(silver(&mut parsed), 6440);
+        let parsed = parse(INPUT).unwrap();
+        assert_eq!(silver(&parsed).unwrap(), 6440);
     }
     #[test]
     fn test_gold() {
-        let mut parsed = parse(INPUT);
-        assert_eq!(gold(&mut parsed), 5905);
+        let parsed = parse(INPUT).unwrap();
+        assert_eq!(gold(&parsed).unwrap(), 5905);
     }
 }
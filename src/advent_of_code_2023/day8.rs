@@ -1,81 +1,349 @@
-use std::collections::HashMap;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt,
+};
 
-use crate::{aoc_result::AOCResult, read_file::read_file};
+use num::Integer;
 
-pub fn solve() -> AOCResult {
-    let input = read_file(2023, 8).expect("File input/2023/08.txt");
-    let program = parse::parse(&input);
-    (silver(&program), gold(&program)).into()
-}
+use crate::{
+    aoc_error::AocError,
+    dot::Dot,
+    solver::{PartOutput, Solver},
+};
 
-// ASCII for 'A' 3 times
-const AAA: u32 = 0x0041_4141;
-const ZZZ: u32 = 0x005A_5A5A;
+pub struct Day8;
 
-fn silver(program: &Program) -> u32 {
-    let instructions = program.instructions.iter().cycle().zip(1u32..);
+impl Solver for Day8 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 8;
 
-    let mut current_instruction = AAA;
-    for (instruction, index) in instructions {
-        let lr = program.map.get(&current_instruction).unwrap();
-        current_instruction = *instruction.choose(lr);
-        if current_instruction == ZZZ {
-            return index;
-        }
+    type Input = Program;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse::parse(input)
+    }
+
+    fn silver(input: &Self::Input) -> impl PartOutput {
+        silver(input)
+    }
+
+    fn gold(input: &Self::Input) -> impl PartOutput {
+        gold(input)
+    }
+
+    fn dot(input: &Self::Input) -> Result<Option<Dot>, AocError> {
+        Ok(Some(to_dot(
+            input,
+            NamePattern::Suffix("A"),
+            NamePattern::Suffix("Z"),
+        )))
     }
+}
+
+fn silver(program: &Program) -> Result<u64, AocError> {
+    steps(program, NamePattern::Name("AAA"), NamePattern::Name("ZZZ"))
+}
 
-    panic!("This should not happen !");
+fn gold(program: &Program) -> Result<u64, AocError> {
+    steps(program, NamePattern::Suffix("A"), NamePattern::Suffix("Z"))
 }
 
-fn gold(program: &Program) -> u64 {
-    find_factors(program)
-        .into_iter()
-        .map(u64::from)
-        .fold(1, num::integer::lcm)
+/// Which node names a walk starts or ends on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamePattern<'a> {
+    Name(&'a str),
+    Suffix(&'a str),
 }
 
-fn find_factors(program: &Program) -> Vec<u32> {
-    let instructions = program.instructions.iter().copied().cycle().zip(1u32..);
+impl NamePattern<'_> {
+    pub fn matches(self, name: &str) -> bool {
+        match self {
+            Self::Name(expected) => name == expected,
+            Self::Suffix(suffix) => name.ends_with(suffix),
+        }
+    }
+}
+
+impl fmt::Display for NamePattern<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => write!(f, "{name}"),
+            Self::Suffix(suffix) => write!(f, "*{suffix}"),
+        }
+    }
+}
 
-    let mut current_instruction: Vec<u32> = program
-        .map
-        .keys()
-        .copied()
-        .filter(|key| end_with(*key, b'A'))
+const ALIGNED_STEP: &str = "the step where every walk reaches an end";
+/// Most combinations of hits kept before the other walks are checked step by step.
+const MAX_CONGRUENCES: usize = 1 << 10;
+/// Most steps checked one by one before giving up.
+const MAX_CANDIDATES: u64 = 1 << 24;
+
+/// First step at which a walk from each node matching `start` stands on a node matching `end`,
+/// all at once.
+pub fn steps(program: &Program, start: NamePattern, end: NamePattern) -> Result<u64, AocError> {
+    let is_end: Vec<_> = program.names.iter().map(|name| end.matches(name)).collect();
+    let walks: Vec<_> = (0..program.names.len())
+        .filter(|node| start.matches(&program.names[*node]))
+        .map(|node| Walk::new(program, node, &is_end))
         .collect();
+    if walks.is_empty() {
+        return Err(AocError::solver(format!("no node matches {start}")));
+    }
+    let overflow = || AocError::overflow(ALIGNED_STEP);
 
-    let capacity = current_instruction.capacity();
-    let mut result_cycles: Vec<u32> = Vec::with_capacity(capacity);
+    // before every walk is in its cycle, simply try each step
+    let cycles_start = walks.iter().map(|walk| walk.cycle_start).max().unwrap_or(0);
+    if let Some(step) = (1..cycles_start).find(|step| walks.iter().all(|walk| walk.hits(*step))) {
+        return Ok(step);
+    }
 
-    for (instruction, index) in instructions {
-        let new_ins = current_instruction
+    let never = || {
+        AocError::solver(format!(
+            "the walks from {start} never all stand on {end} at once"
+        ))
+    };
+
+    // then each walk stands on an end at the steps congruent to one of its hits: combine the
+    // walks with the fewest hits while the combinations stay few, and step through the rest
+    let mut pending: Vec<_> = walks.iter().collect();
+    pending.sort_by_key(|walk| walk.cycle_hits.len());
+    let mut pending = pending.into_iter().peekable();
+    let mut congruences = vec![Congruence {
+        residue: 0,
+        modulus: 1,
+    }];
+    while let Some(walk) = pending
+        .next_if(|walk| congruences.len().saturating_mul(walk.cycle_hits.len()) <= MAX_CONGRUENCES)
+    {
+        let mut combined = Vec::new();
+        for congruence in &congruences {
+            for hit in &walk.cycle_hits {
+                let hit = Congruence {
+                    residue: u128::from(*hit % walk.cycle_length),
+                    modulus: u128::from(walk.cycle_length),
+                };
+                combined.extend(congruence.combine(&hit)?);
+            }
+        }
+        combined.sort_unstable();
+        combined.dedup();
+        congruences = combined;
+    }
+    let first = u128::from(cycles_start.max(1));
+    let rest: Vec<_> = pending.collect();
+
+    let step = if rest.is_empty() {
+        congruences
+            .iter()
+            .map(|congruence| congruence.first_from(first))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(overflow)?
             .into_iter()
-            .map(|cur| *instruction.choose(program.map.get(&cur).unwrap()));
-        current_instruction = Vec::with_capacity(capacity);
-        for current in new_ins {
-            if end_with(current, b'Z') {
-                result_cycles.push(index);
-            } else {
-                current_instruction.push(current);
+            .min()
+            .ok_or_else(never)?
+    } else {
+        let modulus = congruences.first().ok_or_else(never)?.modulus;
+        let residues: Vec<_> = congruences.iter().map(|c| c.residue).collect();
+        // every walk is back where it was after the lcm of all cycles, if that fits
+        let period = rest.iter().try_fold(modulus, |lcm, walk| {
+            let length = u128::from(walk.cycle_length);
+            lcm.checked_mul(length / lcm.gcd(&length))
+        });
+        let mut candidates = 0;
+        let mut base = first - first % modulus;
+        'search: loop {
+            for residue in &residues {
+                let step = base.checked_add(*residue).ok_or_else(overflow)?;
+                if step < first {
+                    continue;
+                }
+                if period.is_some_and(|period| step - first >= period) {
+                    return Err(never());
+                }
+                candidates += 1;
+                if candidates > MAX_CANDIDATES {
+                    return Err(AocError::solver(format!(
+                        "too many steps to try before the walks from {start} align"
+                    )));
+                }
+                let step = u64::try_from(step).map_err(|_| overflow())?;
+                if rest.iter().all(|walk| walk.hits(step)) {
+                    break 'search u128::from(step);
+                }
             }
+            base = base.checked_add(modulus).ok_or_else(overflow)?;
         }
+    };
+    u64::try_from(step).map_err(|_| overflow())
+}
 
-        if current_instruction.is_empty() {
-            return result_cycles;
+/// The network with an edge per instruction, the nodes matching `start` and `end` highlighted.
+pub fn to_dot(program: &Program, start: NamePattern, end: NamePattern) -> Dot {
+    let mut dot = Dot::digraph("network");
+    for name in &program.names {
+        if start.matches(name) {
+            dot.node(name, &[("style", "filled"), ("fillcolor", "palegreen")]);
+        } else if end.matches(name) {
+            dot.node(name, &[("style", "filled"), ("fillcolor", "salmon")]);
         }
     }
+    for (name, (left, right)) in program.names.iter().zip(&program.network) {
+        let (left, right) = (&program.names[*left], &program.names[*right]);
+        if left == right {
+            dot.edge(name, left, &[("label", "LR")]);
+        } else {
+            dot.edge(name, left, &[("label", "L")]);
+            dot.edge(name, right, &[("label", "R")]);
+        }
+    }
+    dot
+}
+
+/// Steps at which one walk stands on an end node, as a prefix then a cycle.
+#[derive(Debug, PartialEq, Eq)]
+struct Walk {
+    prefix_hits: Vec<u64>,
+    /// First step of the cycle, which repeats the same node at the same instruction.
+    cycle_start: u64,
+    cycle_length: u64,
+    /// Hits from the start of the cycle, before it repeats.
+    cycle_hits: Vec<u64>,
+}
+
+impl Walk {
+    fn new(program: &Program, start: usize, is_end: &[bool]) -> Self {
+        let instructions = program.instructions.len();
+        // step at which each node was left with each instruction
+        let mut seen = vec![None; program.network.len() * instructions];
+        let mut hits = Vec::new();
+        let mut node = start;
+        let mut step = 0;
+
+        loop {
+            let instruction = (step % instructions as u64) as usize;
+            let state = node * instructions + instruction;
+            if let Some(cycle_start) = seen[state] {
+                let (prefix_hits, cycle_hits) = hits.iter().partition(|hit| **hit < cycle_start);
+                return Self {
+                    prefix_hits,
+                    cycle_start,
+                    cycle_length: step - cycle_start,
+                    cycle_hits,
+                };
+            }
+            seen[state] = Some(step);
+            if is_end[node] {
+                hits.push(step);
+            }
+            node = *program.instructions[instruction].choose(&program.network[node]);
+            step += 1;
+        }
+    }
+
+    fn hits(&self, step: u64) -> bool {
+        if step < self.cycle_start {
+            self.prefix_hits.contains(&step)
+        } else {
+            let offset = (step - self.cycle_start) % self.cycle_length;
+            self.cycle_hits.contains(&(self.cycle_start + offset))
+        }
+    }
+}
+
+/// The steps equal to `residue` modulo `modulus`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Congruence {
+    residue: u128,
+    modulus: u128,
+}
+
+impl Congruence {
+    /// Steps satisfying both, by the Chinese remainder theorem: `None` when there are none.
+    fn combine(&self, other: &Self) -> Result<Option<Self>, AocError> {
+        let overflow = || AocError::overflow(ALIGNED_STEP);
+        let gcd = self.modulus.gcd(&other.modulus);
+        let difference = (other.residue % other.modulus + other.modulus
+            - self.residue % other.modulus)
+            % other.modulus;
+        if !difference.is_multiple_of(gcd) {
+            return Ok(None);
+        }
+
+        let (modulus, other_modulus) = (self.modulus / gcd, other.modulus / gcd);
+        let lcm = modulus
+            .checked_mul(other.modulus)
+            .filter(|lcm| i128::try_from(*lcm).is_ok())
+            .ok_or_else(overflow)?;
+        // self.residue + self.modulus * k matches other once k = difference / gcd / modulus
+        let inverse = modular_inverse(modulus % other_modulus, other_modulus);
+        let k = (difference / gcd % other_modulus)
+            .checked_mul(inverse)
+            .ok_or_else(overflow)?
+            % other_modulus;
+        let residue = self
+            .modulus
+            .checked_mul(k)
+            .and_then(|offset| offset.checked_add(self.residue))
+            .ok_or_else(overflow)?
+            % lcm;
+        Ok(Some(Self {
+            residue,
+            modulus: lcm,
+        }))
+    }
 
-    panic!("Should not happen");
+    /// Smallest step from `first` on, `None` when it does not fit.
+    fn first_from(&self, first: u128) -> Option<u128> {
+        let behind = (first % self.modulus + self.modulus - self.residue) % self.modulus;
+        match behind {
+            0 => Some(first),
+            behind => first.checked_add(self.modulus - behind),
+        }
+    }
 }
 
-const fn end_with(number: u32, test: u8) -> bool {
-    number & 0xFF == test as u32
+/// Inverse of `value` modulo `modulus`, which must be coprime with it and fit in an `i128`.
+fn modular_inverse(value: u128, modulus: u128) -> u128 {
+    let (mut old, mut current) = (value as i128, modulus as i128);
+    let (mut old_coefficient, mut coefficient) = (1i128, 0i128);
+    while current != 0 {
+        let quotient = old / current;
+        (old, current) = (current, old - quotient * current);
+        (old_coefficient, coefficient) = (coefficient, old_coefficient - quotient * coefficient);
+    }
+    let modulus = modulus as i128;
+    old_coefficient.rem_euclid(modulus) as u128
 }
 
 #[derive(Debug)]
-struct Program {
+pub struct Program {
     instructions: Vec<Instruction>,
-    map: HashMap<u32, (u32, u32)>,
+    /// Node names, indexed by their symbol.
+    names: Vec<String>,
+    /// Left and right neighbours of each node, indexed by its symbol.
+    network: Vec<(usize, usize)>,
+}
+
+/// Node names interned to dense indices, in order of definition.
+#[derive(Debug, Default)]
+struct Symbols<'a> {
+    indices: HashMap<&'a str, usize>,
+}
+
+impl<'a> Symbols<'a> {
+    /// `None` when `name` is already interned.
+    fn intern(&mut self, name: &'a str) -> Option<usize> {
+        let index = self.indices.len();
+        match self.indices.entry(name) {
+            Entry::Occupied(_) => None,
+            Entry::Vacant(entry) => Some(*entry.insert(index)),
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -106,30 +374,60 @@ impl TryFrom<char> for Instruction {
 }
 
 mod parse {
-    use std::collections::HashMap;
+    use nom::{character::complete::alphanumeric1, error::context};
+
+    use super::{AocError, Instruction, Program, Symbols};
+    use crate::diagnostic::{self, ParseResult};
 
-    use nom::{character::complete::alphanumeric1, IResult};
+    type Node<'a> = (&'a str, (&'a str, &'a str));
 
-    use super::{Instruction, Program};
+    pub fn parse(input: &str) -> Result<Program, AocError> {
+        let (instructions, nodes) = diagnostic::parse_with(input, parse_internal)?;
 
-    pub fn parse(input: &str) -> Program {
-        parse_internal(input).unwrap().1
+        let mut symbols = Symbols::default();
+        for (key, _) in &nodes {
+            if symbols.intern(key).is_none() {
+                return Err(AocError::parse(
+                    input,
+                    key,
+                    format!("node {key} is defined twice"),
+                ));
+            }
+        }
+
+        let symbol = |name: &str| {
+            symbols
+                .get(name)
+                .ok_or_else(|| AocError::parse(input, name, format!("node {name} is not defined")))
+        };
+        let network = nodes
+            .iter()
+            .map(|(_, (left, right))| Ok((symbol(left)?, symbol(right)?)))
+            .collect::<Result<_, AocError>>()?;
+
+        Ok(Program {
+            instructions,
+            names: nodes.iter().map(|(key, _)| (*key).to_owned()).collect(),
+            network,
+        })
     }
 
-    fn parse_internal(input: &str) -> IResult<&str, Program> {
-        let (input, instructions) = nom::multi::fold_many1(
-            nom::character::complete::one_of("LR"),
-            Vec::new,
-            |mut acc: Vec<Instruction>, elem: char| {
-                let instruction: Instruction = elem.try_into().unwrap();
-                acc.push(instruction);
-                acc
-            },
+    fn parse_internal(input: &str) -> ParseResult<'_, (Vec<Instruction>, Vec<Node<'_>>)> {
+        let (input, instructions) = context(
+            "L/R instruction",
+            nom::sequence::terminated(
+                nom::multi::many1(nom::combinator::map_res(
+                    nom::character::complete::anychar,
+                    Instruction::try_from,
+                )),
+                nom::multi::many1(nom::character::complete::newline),
+            ),
         )(input)?;
 
-        let (input, map) = nom::sequence::preceded(
-            nom::multi::many1(nom::character::complete::newline),
-            nom::multi::fold_many1(
+        let (input, nodes) = nom::multi::separated_list1(
+            nom::character::complete::newline,
+            context(
+                "node definition",
                 nom::sequence::tuple((
                     nom::sequence::terminated(alphanumeric1, nom::bytes::complete::tag(" = (")),
                     nom::sequence::terminated(
@@ -138,32 +436,19 @@ mod parse {
                             nom::bytes::complete::tag(", "),
                             alphanumeric1,
                         ),
-                        nom::bytes::complete::tag(")\n"),
+                        nom::bytes::complete::tag(")"),
                     ),
                 )),
-                HashMap::new,
-                |mut map: HashMap<u32, (u32, u32)>, elem| {
-                    let key = str_to_u32(elem.0);
-                    let tuple = (str_to_u32(elem.1 .0), str_to_u32(elem.1 .1));
-                    assert!(map.insert(key, tuple).is_none());
-                    map
-                },
             ),
         )(input)?;
 
-        Ok((input, Program { instructions, map }))
-    }
-
-    pub fn str_to_u32(input: &str) -> u32 {
-        input
-            .bytes()
-            .fold(0, |acc, byte| (acc << 8) + u32::from(byte))
+        Ok((input, (instructions, nodes)))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::{gold, parse, silver};
+    use super::{gold, parse, silver, steps, to_dot, NamePattern, Walk};
 
     const SILVER_INPUT: &str = "LLR
 
@@ -184,15 +469,161 @@ ZZZ = (ZZZ, ZZZ)
 XXX = (XXX, XXX)
 ";
 
+    #[test]
+    fn test_parse_invalid_instruction() {
+        let error = parse::parse("LRX\n\nAAA = (AAA, AAA)").err().unwrap();
+        assert!(error
+            .to_string()
+            .starts_with("Parse error at line 1, column 3: expected L/R instruction, found 'X'"));
+    }
+
     #[test]
     fn test_silver() {
-        let parsed = parse::parse(SILVER_INPUT);
-        assert_eq!(silver(&parsed), 6);
+        let parsed = parse::parse(SILVER_INPUT).unwrap();
+        assert_eq!(silver(&parsed).unwrap(), 6);
     }
 
     #[test]
     fn test_gold() {
-        let parsed = parse::parse(GOLD_INPUT);
-        assert_eq!(gold(&parsed), 6);
+        let parsed = parse::parse(GOLD_INPUT).unwrap();
+        assert_eq!(gold(&parsed).unwrap(), 6);
+    }
+
+    #[test]
+    fn test_gold_offset_cycles() {
+        // 11A is on Z every even step, 22A one step in then every 3 steps: a plain lcm gives 2
+        let parsed = parse::parse(
+            "L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22C, 22C)
+22C = (22D, 22D)
+22D = (22Z, 22Z)",
+        )
+        .unwrap();
+        assert_eq!(gold(&parsed).unwrap(), 4);
+
+        let is_end: Vec<_> = parsed
+            .names
+            .iter()
+            .map(|name| name.ends_with('Z'))
+            .collect();
+        let walk = Walk::new(&parsed, 3, &is_end);
+        assert_eq!(parsed.names[3], "22A");
+        assert_eq!(
+            walk,
+            Walk {
+                prefix_hits: vec![],
+                cycle_start: 1,
+                cycle_length: 3,
+                cycle_hits: vec![1],
+            }
+        );
+    }
+
+    #[test]
+    fn test_gold_never_aligned() {
+        let parsed = parse::parse(
+            "L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
+22B = (22Z, 22Z)",
+        )
+        .unwrap();
+        let error = gold(&parsed).unwrap_err().to_string();
+        assert!(
+            error.ends_with("the walks from *A never all stand on *Z at once"),
+            "{error}"
+        );
+    }
+
+    /// Ghosts walking cycles of prime lengths, on an end at every step but the multiples of 3.
+    fn many_hits(ghosts: usize) -> String {
+        let mut input = String::from("L\n");
+        for (ghost, length) in [7, 11, 13, 17, 19, 23].into_iter().take(ghosts).enumerate() {
+            let name = |k: usize| {
+                format!(
+                    "G{ghost}N{k}{}",
+                    if k.is_multiple_of(3) { 'X' } else { 'Z' }
+                )
+            };
+            input += &format!("\nG{ghost}A = ({0}, {0})", name(0));
+            for k in 0..length {
+                input += &format!("\n{} = ({1}, {1})", name(k), name((k + 1) % length));
+            }
+        }
+        input
+    }
+
+    #[test]
+    fn test_gold_many_hits() {
+        for ghosts in 1..=6 {
+            let parsed = parse::parse(&many_hits(ghosts)).unwrap();
+            let mut nodes: Vec<_> = (0..parsed.names.len())
+                .filter(|node| parsed.names[*node].ends_with('A'))
+                .collect();
+            let mut naive = 0;
+            while !nodes.iter().all(|node| parsed.names[*node].ends_with('Z')) {
+                nodes = nodes.iter().map(|node| parsed.network[*node].0).collect();
+                naive += 1;
+            }
+            assert_eq!(gold(&parsed).unwrap(), naive, "{ghosts} ghosts");
+        }
+    }
+
+    #[test]
+    fn test_dot() {
+        let parsed = parse::parse(SILVER_INPUT).unwrap();
+        let dot = to_dot(&parsed, NamePattern::Name("AAA"), NamePattern::Name("ZZZ"));
+        assert_eq!(
+            dot.to_string(),
+            "digraph \"network\" {
+  \"AAA\" [style=\"filled\", fillcolor=\"palegreen\"];
+  \"ZZZ\" [style=\"filled\", fillcolor=\"salmon\"];
+  \"AAA\" -> \"BBB\" [label=\"LR\"];
+  \"BBB\" -> \"AAA\" [label=\"L\"];
+  \"BBB\" -> \"ZZZ\" [label=\"R\"];
+  \"ZZZ\" -> \"ZZZ\" [label=\"LR\"];
+}"
+        );
+    }
+
+    #[test]
+    fn test_long_names() {
+        // packed into a u32, the first five characters would have collided
+        let parsed = parse::parse(
+            "RL
+
+START = (BRANCHLEFT, BRANCHRIGHT)
+BRANCHLEFT = (START, START)
+BRANCHRIGHT = (FINISH, START)
+FINISH = (FINISH, FINISH)",
+        )
+        .unwrap();
+        let (start, end) = (NamePattern::Name("START"), NamePattern::Name("FINISH"));
+        assert_eq!(steps(&parsed, start, end).unwrap(), 2);
+
+        let error = steps(&parsed, NamePattern::Suffix("Q"), end).unwrap_err();
+        assert!(error.to_string().ends_with("no node matches *Q"), "{error}");
+        let error = silver(&parsed).unwrap_err();
+        assert!(
+            error.to_string().ends_with("no node matches AAA"),
+            "{error}"
+        );
+
+        let error = parse::parse("L\n\nAAA = (AAA, ZZZ)").err().unwrap();
+        assert!(
+            error
+                .to_string()
+                .starts_with("Parse error at line 3, column 13: node ZZZ is not defined"),
+            "{error}"
+        );
     }
 }
@@ -1,74 +1,100 @@
-use crate::{aoc_result::AOCResult, read_file::read_file};
+use crate::{
+    aoc_error::AocError,
+    checked,
+    solver::{PartOutput, Solver},
+};
 
 use self::parse::parse;
 
-pub fn solve() -> AOCResult {
-    let input = read_file(2023, 9).expect("File input/2023/09.txt");
-    let parsed = parse(&input);
+pub struct Day9;
 
-    (silver(&parsed), gold(&parsed)).into()
+impl Solver for Day9 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 9;
+
+    type Input = Vec<Line>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
+
+    fn silver(input: &Self::Input) -> impl PartOutput {
+        silver(input)
+    }
+
+    fn gold(input: &Self::Input) -> impl PartOutput {
+        gold(input)
+    }
 }
 
-fn silver(input: &[Line]) -> i32 {
-    input.iter().map(|line| silver_line(line.as_ref())).sum()
+fn silver(input: &[Line]) -> Result<i64, AocError> {
+    extrapolate(input, silver_line)
 }
 
-fn silver_line(input: &[i32]) -> i32 {
+/// `None` when a difference or the next value overflows.
+fn silver_line(input: &[i64]) -> Option<i64> {
     if input.iter().all(|e| *e == 0) {
-        0
+        Some(0)
     } else {
-        let derived = derive(input);
-        input.last().unwrap() + silver_line(&derived)
+        let derived = derive(input)?;
+        input.last().unwrap().checked_add(silver_line(&derived)?)
     }
 }
 
-fn gold(input: &[Line]) -> i32 {
-    input.iter().map(|line| gold_line(line.as_ref())).sum()
+fn gold(input: &[Line]) -> Result<i64, AocError> {
+    extrapolate(input, gold_line)
 }
 
-fn gold_line(input: &[i32]) -> i32 {
+/// `None` when a difference or the previous value overflows.
+fn gold_line(input: &[i64]) -> Option<i64> {
     if input.iter().all(|e| *e == 0) {
-        0
+        Some(0)
     } else {
-        let derived = derive(input);
-        input.first().unwrap() - gold_line(&derived)
+        let derived = derive(input)?;
+        input.first().unwrap().checked_sub(gold_line(&derived)?)
     }
 }
 
-fn derive(input: &[i32]) -> Vec<i32> {
-    let mut iter = input.iter();
-    let mut previous = *iter.next().unwrap();
-    let mut result = Vec::new();
-
-    for elem in iter {
-        result.push(elem - previous);
-        previous = *elem;
-    }
+fn extrapolate(input: &[Line], line: fn(&[i64]) -> Option<i64>) -> Result<i64, AocError> {
+    let values = input
+        .iter()
+        .map(|history| line(history))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| AocError::overflow("an extrapolated value"))?;
+    checked::sum(values, "the sum of extrapolated values")
+}
 
-    result
+fn derive(input: &[i64]) -> Option<Vec<i64>> {
+    input
+        .windows(2)
+        .map(|pair| pair[1].checked_sub(pair[0]))
+        .collect()
 }
 
-type Line = Vec<i32>;
+type Line = Vec<i64>;
 
 mod parse {
-    use super::Line;
-    use nom::IResult;
+    use super::{AocError, Line};
+    use crate::diagnostic::{self, ParseResult};
 
-    pub fn parse(input: &str) -> Vec<Line> {
-        parse_internal(input).unwrap().1
+    pub fn parse(input: &str) -> Result<Vec<Line>, AocError> {
+        diagnostic::parse_with(input, parse_internal)
     }
 
-    fn parse_internal(input: &str) -> IResult<&str, Vec<Line>> {
+    fn parse_internal(input: &str) -> ParseResult<'_, Vec<Line>> {
         let (input, lines) =
             nom::multi::separated_list1(nom::character::complete::newline, line)(input)?;
 
         Ok((input, lines))
     }
 
-    fn line(input: &str) -> IResult<&str, Line> {
-        let (input, line) = nom::multi::separated_list1(
-            nom::bytes::complete::tag(" "),
-            nom::character::complete::i32,
+    fn line(input: &str) -> ParseResult<'_, Line> {
+        let (input, line) = nom::error::context(
+            "history of values",
+            nom::multi::separated_list1(
+                nom::bytes::complete::tag(" "),
+                nom::character::complete::i64,
+            ),
         )(input)?;
 
         Ok((input, line))
@@ -85,7 +111,7 @@ mod test {
 
     #[test]
     fn test_parse() {
-        let parsed = parse(INPUT);
+        let parsed = parse(INPUT).unwrap();
 
         assert_eq!(
             parsed,
@@ -99,15 +125,15 @@ mod test {
 
     #[test]
     fn test_silver() {
-        let parsed = parse(INPUT);
+        let parsed = parse(INPUT).unwrap();
 
-        assert_eq!(silver(&parsed), 114);
+        assert_eq!(silver(&parsed).unwrap(), 114);
     }
 
     #[test]
     fn test_gold() {
-        let parsed = parse(INPUT);
+        let parsed = parse(INPUT).unwrap();
 
-        assert_eq!(gold(&parsed), 2);
+        assert_eq!(gold(&parsed).unwrap(), 2);
     }
 }
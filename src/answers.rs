@@ -0,0 +1,242 @@
+use std::{
+    collections::BTreeMap,
+    env, fmt, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    aoc_error::AocError,
+    aoc_result::{Answer, Part},
+};
+
+/// Default answers file, meant to be committed next to the sources.
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+const HASH_PREFIX: &str = "sha256:";
+
+/// Closest [`ANSWERS_FILE`] from the current directory, found the way inputs are: in the first
+/// ancestor holding either that file or an `input` directory.
+pub fn default_path() -> PathBuf {
+    env::current_dir()
+        .ok()
+        .and_then(|current| closest(&current))
+        .unwrap_or_else(|| PathBuf::from(ANSWERS_FILE))
+}
+
+fn closest(from: &Path) -> Option<PathBuf> {
+    from.ancestors()
+        .find(|dir| dir.join(ANSWERS_FILE).is_file() || dir.join("input").is_dir())
+        .map(|dir| dir.join(ANSWERS_FILE))
+}
+
+/// Known answer of a single part, either in clear or as a salted hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expected {
+    Plain(String),
+    Hashed { salt: String, digest: String },
+}
+
+impl Expected {
+    /// Hash `answer` with a fresh salt from the system's random source.
+    pub fn hashed(answer: &Answer) -> Result<Self, AocError> {
+        let mut bytes = [0; 8];
+        getrandom::getrandom(&mut bytes)
+            .map_err(|error| AocError::solver(format!("no random salt to hash with: {error}")))?;
+        let salt = format!("{:016x}", u64::from_le_bytes(bytes));
+        let digest = digest(&salt, answer);
+        Ok(Self::Hashed { salt, digest })
+    }
+
+    pub fn matches(&self, answer: &Answer) -> bool {
+        match self {
+            Self::Plain(expected) => *expected == answer.to_string(),
+            Self::Hashed {
+                salt,
+                digest: expected,
+            } => *expected == digest(salt, answer),
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        if value.is_empty() {
+            return None;
+        }
+        let Some(hashed) = value.strip_prefix(HASH_PREFIX) else {
+            return Some(Self::Plain(value.to_owned()));
+        };
+
+        let (salt, digest) = hashed.split_once(':')?;
+        let is_hex = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_hexdigit());
+        (is_hex(salt) && is_hex(digest)).then(|| Self::Hashed {
+            salt: salt.to_owned(),
+            digest: digest.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Plain(answer) => write!(f, "{answer}"),
+            Self::Hashed { salt, digest } => write!(f, "{HASH_PREFIX}{salt}:{digest}"),
+        }
+    }
+}
+
+fn digest(salt: &str, answer: &Answer) -> String {
+    let hash = Sha256::new()
+        .chain_update(salt)
+        .chain_update(answer.to_string())
+        .finalize();
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Answers store: one `YEAR DAY PART ANSWER` line per known answer.
+#[derive(Debug, Default)]
+pub struct Answers {
+    path: PathBuf,
+    entries: BTreeMap<(u16, u8, Part), Expected>,
+}
+
+impl Answers {
+    /// Load the store at `path`, an absent file being an empty store.
+    pub fn load(path: &Path) -> Result<Self, AocError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(AocError::Io(path.to_owned(), error)),
+        };
+
+        let entries = parse(&content).map_err(|error| error.in_file(path.to_owned()))?;
+        Ok(Self {
+            path: path.to_owned(),
+            entries,
+        })
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: Part) -> Option<&Expected> {
+        self.entries.get(&(year, day, part))
+    }
+
+    /// Record `expected`, never replacing an answer that is already known.
+    pub fn record(&mut self, year: u16, day: u8, part: Part, expected: Expected) -> bool {
+        let key = (year, day, part);
+        if self.entries.contains_key(&key) {
+            return false;
+        }
+        self.entries.insert(key, expected);
+        true
+    }
+
+    pub fn save(&self) -> Result<(), AocError> {
+        fs::write(&self.path, self.to_string())
+            .map_err(|error| AocError::Io(self.path.clone(), error))
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# year day part answer")?;
+        for ((year, day, part), expected) in &self.entries {
+            writeln!(f, "{year} {day} {} {expected}", part.name())?;
+        }
+        Ok(())
+    }
+}
+
+fn parse(content: &str) -> Result<BTreeMap<(u16, u8, Part), Expected>, AocError> {
+    let mut entries = BTreeMap::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let error = |message: &str| AocError::parse(content, line, message);
+        let mut fields = trimmed.splitn(4, ' ');
+        let mut field = || fields.next().unwrap_or_default();
+
+        let year = field().parse().map_err(|_| error("expected a year"))?;
+        let day = field().parse().map_err(|_| error("expected a day"))?;
+        let part = match field() {
+            "silver" => Part::Silver,
+            "gold" => Part::Gold,
+            _ => return Err(error("expected silver or gold")),
+        };
+        let expected = Expected::parse(field())
+            .ok_or_else(|| error("expected an answer or sha256:SALT:DIGEST"))?;
+
+        if entries.insert((year, day, part), expected).is_some() {
+            return Err(error("answer is recorded twice"));
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::{closest, parse, Answers, Expected, ANSWERS_FILE};
+    use crate::aoc_result::{Answer, Part};
+
+    #[test]
+    fn test_hashed() {
+        let expected = Expected::hashed(&Answer::Unsigned(35)).unwrap();
+        assert!(expected.matches(&Answer::Unsigned(35)));
+        assert!(!expected.matches(&Answer::Unsigned(46)));
+        assert_eq!(
+            Expected::parse(&expected.to_string()),
+            Some(expected.clone())
+        );
+
+        let again = Expected::hashed(&Answer::Unsigned(35)).unwrap();
+        assert_ne!(again, expected, "every hash gets its own salt");
+    }
+
+    #[test]
+    fn test_closest() {
+        let root = std::env::temp_dir().join(format!("answers-closest-{}", std::process::id()));
+        let nested = root.join("src/advent_of_code_2023");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(root.join("input")).unwrap();
+        assert_eq!(closest(&nested), Some(root.join(ANSWERS_FILE)));
+
+        fs::write(nested.join(ANSWERS_FILE), "").unwrap();
+        assert_eq!(closest(&nested), Some(nested.join(ANSWERS_FILE)));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_round_trip() {
+        const CONTENT: &str = "# year day part answer
+2023 5 silver 35
+2023 5 gold sha256:00ff:\
+4b6a1d6d1bb03386b8e9a2b8a66e1e3ee3e4e43a5e3e4a5c3e0c1e0b1f8b6a10
+";
+        let entries = parse(CONTENT).unwrap();
+        assert_eq!(
+            entries.get(&(2023, 5, Part::Silver)),
+            Some(&Expected::Plain("35".to_owned()))
+        );
+
+        let answers = Answers {
+            entries,
+            ..Answers::default()
+        };
+        assert_eq!(answers.to_string(), CONTENT);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("2023 5 silver 35\n2023 5 bronze 35").unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+        assert!(parse("2023 5 silver 35\n2023 5 silver 36").is_err());
+        assert!(parse("2023 5 gold sha256:xyz").is_err());
+    }
+}
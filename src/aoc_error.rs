@@ -0,0 +1,80 @@
+use std::{fmt, io, path::PathBuf};
+
+use crate::diagnostic::Diagnostic;
+
+#[derive(Debug)]
+pub enum AocError {
+    Usage(String),
+    MissingInput(PathBuf),
+    Io(PathBuf, io::Error),
+    Parse(Diagnostic),
+    UnsupportedYear(u16),
+    UnsupportedDay(u16, u8),
+    SolverFailure(String),
+    /// `new` could not generate or register a day.
+    Scaffold(String),
+    /// An answer or intermediate value does not fit in its integer type.
+    Overflow(String),
+}
+
+impl AocError {
+    /// Parse error located at `at`, which must be a sub-slice of `input`.
+    pub fn parse(input: &str, at: &str, message: impl Into<String>) -> Self {
+        Self::Parse(Diagnostic::new(input, at, message))
+    }
+
+    /// Name the input file in parse diagnostics.
+    #[must_use]
+    pub fn in_file(mut self, file: PathBuf) -> Self {
+        if let Self::Parse(diagnostic) = &mut self {
+            diagnostic.set_file(file);
+        }
+        self
+    }
+
+    pub fn solver(message: impl Into<String>) -> Self {
+        Self::SolverFailure(message.into())
+    }
+
+    pub fn scaffold(message: impl Into<String>) -> Self {
+        Self::Scaffold(message.into())
+    }
+
+    /// `what` overflowed while being computed.
+    pub fn overflow(what: impl Into<String>) -> Self {
+        Self::Overflow(what.into())
+    }
+
+    /// Process exit code, distinct per kind of failure so scripts can tell them apart.
+    pub const fn exit_code(&self) -> i32 {
+        match self {
+            Self::Usage(_) => 2,
+            Self::MissingInput(_) => 3,
+            Self::Io(_, _) => 4,
+            Self::Parse(_) => 5,
+            Self::UnsupportedYear(_) => 6,
+            Self::UnsupportedDay(_, _) => 7,
+            Self::SolverFailure(_) => 8,
+            Self::Scaffold(_) => 9,
+            Self::Overflow(_) => 10,
+        }
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Usage(usage) => write!(f, "{usage}"),
+            Self::MissingInput(path) => write!(f, "Missing input file {}", path.display()),
+            Self::Io(path, error) => write!(f, "Could not read {}: {error}", path.display()),
+            Self::Parse(diagnostic) => write!(f, "{diagnostic}"),
+            Self::UnsupportedYear(year) => write!(f, "Year {year} is not supported"),
+            Self::UnsupportedDay(year, day) => write!(f, "{year}-{day} is not implemented"),
+            Self::SolverFailure(message) => write!(f, "Solver failed: {message}"),
+            Self::Scaffold(message) => write!(f, "Could not create the day: {message}"),
+            Self::Overflow(what) => write!(f, "Arithmetic overflow while computing {what}"),
+        }
+    }
+}
+
+impl std::error::Error for AocError {}
@@ -1,24 +1,154 @@
-#[derive(Debug)]
+use std::{fmt, time::Duration};
+
+/// Typed answer of a single part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+    Text(String),
+}
+
+macro_rules! impl_from_answer {
+    ($variant:ident, $target:ty, $($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                Self::$variant(<$target>::from(value))
+            }
+        })*
+    };
+}
+
+impl_from_answer!(Unsigned, u64, u8, u16, u32, u64);
+impl_from_answer!(Signed, i64, i8, i16, i32, i64);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Self::Unsigned(u64::try_from(value).expect("usize to fit in 64 bits"))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsigned(n) => write!(f, "{n}"),
+            Self::Signed(n) => write!(f, "{n}"),
+            Self::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartResult {
+    /// The part was not selected to run.
+    Skipped,
+    NotImplemented,
+    Solved(Answer),
+}
+
+impl PartResult {
+    /// Short machine-readable name of the outcome.
+    pub const fn status(&self) -> &'static str {
+        match self {
+            Self::Skipped => "skipped",
+            Self::NotImplemented => "not_implemented",
+            Self::Solved(_) => "solved",
+        }
+    }
+}
+
+impl fmt::Display for PartResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Skipped => write!(f, "-"),
+            Self::NotImplemented => write!(f, "not implemented"),
+            Self::Solved(answer) => write!(f, "{answer}"),
+        }
+    }
+}
+
+/// One of the two parts of a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    Silver,
+    Gold,
+}
+
+impl Part {
+    pub const ALL: [Self; 2] = [Self::Silver, Self::Gold];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Silver => "silver",
+            Self::Gold => "gold",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AOCResult {
-    silver: String,
-    gold: String,
+    silver: PartResult,
+    gold: PartResult,
+}
+
+impl AOCResult {
+    pub const fn new(silver: PartResult, gold: PartResult) -> Self {
+        Self { silver, gold }
+    }
+
+    pub const fn silver(&self) -> &PartResult {
+        &self.silver
+    }
+
+    pub const fn gold(&self) -> &PartResult {
+        &self.gold
+    }
+
+    pub const fn part(&self, part: Part) -> &PartResult {
+        match part {
+            Part::Silver => &self.silver,
+            Part::Gold => &self.gold,
+        }
+    }
 }
 
 impl<SILVER, GOLD> From<(SILVER, GOLD)> for AOCResult
 where
-    SILVER: ToString,
-    GOLD: ToString,
+    SILVER: Into<Answer>,
+    GOLD: Into<Answer>,
 {
     fn from((silver, gold): (SILVER, GOLD)) -> Self {
         Self {
-            silver: silver.to_string(),
-            gold: gold.to_string(),
+            silver: PartResult::Solved(silver.into()),
+            gold: PartResult::Solved(gold.into()),
         }
     }
 }
 
-impl std::fmt::Display for AOCResult {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl fmt::Display for AOCResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "({},{})", self.silver, self.gold)
     }
 }
+
+/// Wall time spent in each phase of a single solve.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub silver: Duration,
+    pub gold: Duration,
+}
+
+impl Timings {
+    pub const fn part(&self, part: Part) -> Duration {
+        match part {
+            Part::Silver => self.silver,
+            Part::Gold => self.gold,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    aoc_error::AocError,
+    aoc_result::{AOCResult, Part},
+    output::{self, Record, Value},
+    table,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Silver,
+    Gold,
+}
+
+impl Phase {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Parse => "parse",
+            Self::Silver => "silver",
+            Self::Gold => "gold",
+        }
+    }
+
+    const fn part(self) -> Option<Part> {
+        match self {
+            Self::Parse => None,
+            Self::Silver => Some(Part::Silver),
+            Self::Gold => Some(Part::Gold),
+        }
+    }
+}
+
+/// Wall time statistics of one phase over several runs.
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort_unstable();
+        let total: Duration = samples.iter().sum();
+        let runs = u32::try_from(samples.len()).unwrap_or(u32::MAX);
+
+        Self {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            mean: total / runs,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Benchmark of every selected phase of a single day.
+pub struct Bench {
+    pub year: u16,
+    pub day: u8,
+    pub runs: u32,
+    pub phases: Vec<(Phase, Stats)>,
+    /// Answers of the last run.
+    pub result: AOCResult,
+}
+
+/// Run `phase` `runs` times, stopping at the first failure.
+/// Returns the value of the last run along with the statistics.
+pub fn measure<T>(
+    runs: u32,
+    mut phase: impl FnMut() -> Result<T, AocError>,
+) -> Result<(T, Stats), AocError> {
+    let mut samples = Vec::new();
+    let mut last = None;
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        let value = black_box(phase()?);
+        samples.push(start.elapsed());
+        last = Some(value);
+    }
+
+    let last = last.expect("at least one run");
+    Ok((last, Stats::from_samples(samples)))
+}
+
+pub fn print_table(benches: &[Bench]) {
+    const HEADER: [&str; 8] = [
+        "year", "day", "phase", "runs", "min", "median", "mean", "max",
+    ];
+
+    let rows: Vec<_> = benches
+        .iter()
+        .flat_map(|bench| {
+            bench.phases.iter().map(|(phase, stats)| {
+                vec![
+                    bench.year.to_string(),
+                    bench.day.to_string(),
+                    phase.name().to_owned(),
+                    bench.runs.to_string(),
+                    format!("{:?}", stats.min),
+                    format!("{:?}", stats.median),
+                    format!("{:?}", stats.mean),
+                    format!("{:?}", stats.max),
+                ]
+            })
+        })
+        .collect();
+
+    table::print(&HEADER, &rows);
+}
+
+/// One record per phase, the median being its duration.
+pub fn records(benches: &[Bench]) -> Vec<Record> {
+    benches
+        .iter()
+        .flat_map(|bench| {
+            bench.phases.iter().map(|(phase, stats)| {
+                let result = phase.part().map(|part| bench.result.part(part));
+                let status = result.map_or("parsed", |result| result.status());
+                let mut record = output::part_record(
+                    bench.year,
+                    bench.day,
+                    phase.name(),
+                    result,
+                    Some(stats.median),
+                    status,
+                );
+                record.extend([
+                    ("runs", Value::Number(bench.runs.into())),
+                    ("min_ns", stats.min.into()),
+                    ("median_ns", stats.median.into()),
+                    ("mean_ns", stats.mean.into()),
+                    ("max_ns", stats.max.into()),
+                ]);
+                record
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{measure, Stats};
+    use crate::aoc_error::AocError;
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(
+            [5, 1, 3, 2, 4]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect(),
+        );
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.mean, Duration::from_millis(3));
+        assert_eq!(stats.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_measure_stops_on_failure() {
+        let mut calls = 0;
+        let result = measure(10, || {
+            calls += 1;
+            Err::<(), _>(AocError::solver("boom"))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
@@ -0,0 +1,56 @@
+//! Arithmetic reporting overflows as [`AocError::Overflow`] instead of wrapping or panicking.
+
+use num::{CheckedAdd, CheckedMul, One, Zero};
+
+use crate::aoc_error::AocError;
+
+/// Sum of `values`, `what` naming it in the overflow error.
+pub fn sum<T: CheckedAdd + Zero>(
+    values: impl IntoIterator<Item = T>,
+    what: &str,
+) -> Result<T, AocError> {
+    values
+        .into_iter()
+        .try_fold(T::zero(), |acc, value| acc.checked_add(&value))
+        .ok_or_else(|| AocError::overflow(what))
+}
+
+/// Product of `values`, `what` naming it in the overflow error.
+pub fn product<T: CheckedMul + One>(
+    values: impl IntoIterator<Item = T>,
+    what: &str,
+) -> Result<T, AocError> {
+    values
+        .into_iter()
+        .try_fold(T::one(), |acc, value| acc.checked_mul(&value))
+        .ok_or_else(|| AocError::overflow(what))
+}
+
+/// Least common multiple of `a` and `b`.
+pub fn lcm(a: u64, b: u64, what: &str) -> Result<u64, AocError> {
+    if a == 0 || b == 0 {
+        return Ok(0);
+    }
+    (a / num::integer::gcd(a, b))
+        .checked_mul(b)
+        .ok_or_else(|| AocError::overflow(what))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{lcm, product, sum};
+    use crate::aoc_error::AocError;
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(sum([u64::MAX - 1, 1], "sum").unwrap(), u64::MAX);
+        assert!(matches!(
+            sum([u64::MAX, 1], "sum"),
+            Err(AocError::Overflow(_))
+        ));
+        assert_eq!(product([3_u32, 5, 7], "product").unwrap(), 105);
+        assert!(product([u32::MAX, 2], "product").is_err());
+        assert_eq!(lcm(1 << 40, 3 << 20, "lcm").unwrap(), 3 << 40);
+        assert!(lcm(u64::MAX, u64::MAX - 1, "lcm").is_err());
+    }
+}
@@ -0,0 +1,334 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    answers::default_path,
+    aoc_error::AocError,
+    read_file::{InputSource, INPUT_DIR_ENV},
+    solver::{DumpOptions, Parts},
+};
+
+pub const USAGE: &str =
+    "Usage: [bench|verify] YEAR [DAY] | all | new|dump|dot YEAR DAY [--part silver|gold|both]
+       [--input FILE|-] [--input-dir DIR] [--runs N] [--format text|json|csv|tsv]
+       [--answers FILE] [--record [--hash]] [--limit N] [--count]";
+
+pub enum Command {
+    Solve,
+    Bench {
+        runs: u32,
+    },
+    Verify {
+        record: Option<Record>,
+    },
+    /// Generate and register the module of a new day.
+    New,
+    /// Print how a day solves its input.
+    Dump(DumpOptions),
+    /// Print the structure of a day's input as Graphviz DOT.
+    Dot,
+}
+
+/// How `verify --record` stores the answers that are not known yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Record {
+    Plain,
+    /// Only a salted hash, so the answers file can be shared.
+    Hashed,
+}
+
+pub enum Selection {
+    Day(u16, u8),
+    Year(u16),
+    All,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    /// One JSON object per line.
+    Json,
+    Csv,
+    Tsv,
+}
+
+pub struct Options {
+    pub command: Command,
+    pub selection: Selection,
+    pub parts: Parts,
+    pub format: Format,
+    pub input: InputSource,
+    pub answers: PathBuf,
+}
+
+pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, AocError> {
+    let mut positional = Vec::new();
+    let mut parts = Parts::Both;
+    let mut runs = None;
+    let mut format = Format::Text;
+    let mut input_file = None;
+    let mut input_dir = None;
+    let mut answers = None;
+    let mut record = None;
+    let mut limit = None;
+    let mut count_only = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" => parts = parse_parts(&value(&mut args, &arg)?)?,
+            "--runs" => runs = Some(number(&value(&mut args, &arg)?)?),
+            "--format" => format = parse_format(&value(&mut args, &arg)?)?,
+            "--input" => input_file = Some(value(&mut args, &arg)?),
+            "--input-dir" => input_dir = Some(value(&mut args, &arg)?),
+            "--answers" => answers = Some(PathBuf::from(value(&mut args, &arg)?)),
+            "--record" => record = record.or(Some(Record::Plain)),
+            "--hash" => record = Some(Record::Hashed),
+            "--limit" => limit = Some(number(&value(&mut args, &arg)?)?),
+            "--count" => count_only = true,
+            flag if flag.starts_with("--") => return Err(usage(format!("Unknown flag {flag}"))),
+            _ => positional.push(arg),
+        }
+    }
+
+    let command = match positional.first().map(String::as_str) {
+        Some("bench") => Command::Bench {
+            runs: runs.unwrap_or(10),
+        },
+        Some("verify") => Command::Verify { record },
+        Some("new") => Command::New,
+        Some("dump") => Command::Dump(DumpOptions {
+            limit: limit.unwrap_or(DumpOptions::default().limit),
+            count_only,
+        }),
+        Some("dot") => Command::Dot,
+        _ => Command::Solve,
+    };
+    if !matches!(command, Command::Solve) {
+        positional.remove(0);
+    }
+
+    if !matches!(command, Command::Bench { .. }) && runs.is_some() {
+        return Err(usage("--runs is only valid with bench"));
+    }
+    if runs == Some(0) {
+        return Err(usage("--runs must be at least 1"));
+    }
+    if matches!(
+        command,
+        Command::Verify { .. } | Command::New | Command::Dump(_) | Command::Dot
+    ) && format != Format::Text
+    {
+        return Err(usage(
+            "--format is not supported by verify, new, dump and dot",
+        ));
+    }
+    if !matches!(command, Command::Verify { .. }) && record.is_some() {
+        return Err(usage("--record and --hash are only valid with verify"));
+    }
+    if !matches!(command, Command::Dump(_)) && (limit.is_some() || count_only) {
+        return Err(usage("--limit and --count are only valid with dump"));
+    }
+
+    let selection = match positional.as_slice() {
+        [all] if all == "all" => Selection::All,
+        [year] => Selection::Year(number(year)?),
+        [year, day] => Selection::Day(number(year)?, number(day)?),
+        _ => return Err(usage("Expected YEAR [DAY] or all")),
+    };
+    if matches!(command, Command::New | Command::Dump(_) | Command::Dot)
+        && !matches!(selection, Selection::Day(_, _))
+    {
+        return Err(usage("new, dump and dot expect a single YEAR DAY"));
+    }
+
+    let input = match (input_file, input_dir) {
+        (Some(_), Some(_)) => return Err(usage("--input and --input-dir are exclusive")),
+        (Some(_), None) if !matches!(selection, Selection::Day(_, _)) => {
+            return Err(usage("--input requires a single YEAR DAY"))
+        }
+        (Some(file), None) => InputSource::from(Path::new(&file)),
+        (None, Some(dir)) => InputSource::Root(PathBuf::from(dir)),
+        (None, None) => InputSource::from_env(),
+    };
+
+    Ok(Options {
+        command,
+        selection,
+        parts,
+        format,
+        input,
+        answers: answers.unwrap_or_else(default_path),
+    })
+}
+
+fn parse_parts(arg: &str) -> Result<Parts, AocError> {
+    match arg {
+        "silver" => Ok(Parts::Silver),
+        "gold" => Ok(Parts::Gold),
+        "both" => Ok(Parts::Both),
+        _ => Err(usage(format!("Unknown part {arg:?}"))),
+    }
+}
+
+fn parse_format(arg: &str) -> Result<Format, AocError> {
+    match arg {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        "csv" => Ok(Format::Csv),
+        "tsv" => Ok(Format::Tsv),
+        _ => Err(usage(format!("Unknown format {arg:?}"))),
+    }
+}
+
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, AocError> {
+    args.next()
+        .ok_or_else(|| usage(format!("{flag} expects a value")))
+}
+
+fn number<T: std::str::FromStr>(arg: &str) -> Result<T, AocError> {
+    arg.parse()
+        .map_err(|_| usage(format!("Expected a number, got {arg:?}")))
+}
+
+fn usage(message: impl std::fmt::Display) -> AocError {
+    AocError::Usage(format!(
+        "{message}\n{USAGE}\n\nInputs are read from ${INPUT_DIR_ENV}/YEAR/DD.txt, \
+         or from the closest ./input directory"
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::{parse, Command, Format, Record, Selection};
+    use crate::{
+        read_file::InputSource,
+        solver::{DumpOptions, Parts},
+    };
+
+    fn args(line: &str) -> impl Iterator<Item = String> + '_ {
+        line.split_whitespace().map(str::to_owned)
+    }
+
+    #[test]
+    fn test_parse() {
+        let options = parse(args("2023 5 --part silver")).unwrap();
+        assert!(matches!(options.command, Command::Solve));
+        assert!(matches!(options.selection, Selection::Day(2023, 5)));
+        assert_eq!(options.parts, Parts::Silver);
+
+        let options = parse(args("--part gold all")).unwrap();
+        assert!(matches!(options.selection, Selection::All));
+        assert_eq!(options.parts, Parts::Gold);
+
+        assert!(matches!(
+            parse(args("2023")).unwrap().selection,
+            Selection::Year(2023)
+        ));
+        assert!(parse(args("2023 5 --part platinum")).is_err());
+        assert!(parse(args("2023 five")).is_err());
+        assert!(parse(args("")).is_err());
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        let options = parse(args("bench 2023 --runs 3 --format json")).unwrap();
+        assert!(matches!(options.command, Command::Bench { runs: 3 }));
+        assert!(matches!(options.selection, Selection::Year(2023)));
+        assert_eq!(options.format, Format::Json);
+
+        assert!(matches!(
+            parse(args("bench all")).unwrap().command,
+            Command::Bench { runs: 10 }
+        ));
+        assert!(parse(args("2023 --runs 3")).is_err());
+
+        let error = parse(args("bench 2023 --runs 0"))
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.contains("--runs must be at least 1"), "{error}");
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(
+            parse(args("2023 5 --format csv")).unwrap().format,
+            Format::Csv
+        );
+        assert_eq!(parse(args("all --format tsv")).unwrap().format, Format::Tsv);
+        assert!(parse(args("2023 --format xml")).is_err());
+        assert!(parse(args("verify 2023 --format json")).is_err());
+    }
+
+    #[test]
+    fn test_parse_verify() {
+        let options = parse(args("verify all")).unwrap();
+        assert!(matches!(options.command, Command::Verify { record: None }));
+        assert!(matches!(options.selection, Selection::All));
+
+        let options = parse(args("verify 2023 --record --hash --answers a.txt")).unwrap();
+        assert!(matches!(
+            options.command,
+            Command::Verify {
+                record: Some(Record::Hashed)
+            }
+        ));
+        assert_eq!(options.answers, PathBuf::from("a.txt"));
+
+        assert!(parse(args("2023 --record")).is_err());
+        assert!(parse(args("verify 2023 --runs 3")).is_err());
+    }
+
+    #[test]
+    fn test_parse_new() {
+        let options = parse(args("new 2024 1")).unwrap();
+        assert!(matches!(options.command, Command::New));
+        assert!(matches!(options.selection, Selection::Day(2024, 1)));
+
+        assert!(parse(args("new 2024")).is_err());
+        assert!(parse(args("new all")).is_err());
+    }
+
+    #[test]
+    fn test_parse_dump() {
+        let options = parse(args("dump 2023 5 --input example.txt")).unwrap();
+        assert!(matches!(options.command, Command::Dump(dump) if dump == DumpOptions::default()));
+        assert!(matches!(options.selection, Selection::Day(2023, 5)));
+
+        let options = parse(args("dump 2023 12 --limit 3 --count")).unwrap();
+        assert!(matches!(
+            options.command,
+            Command::Dump(DumpOptions {
+                limit: 3,
+                count_only: true
+            })
+        ));
+        assert!(parse(args("2023 12 --count")).is_err());
+
+        let options = parse(args("dot 2023 8")).unwrap();
+        assert!(matches!(options.command, Command::Dot));
+        assert!(parse(args("dot all")).is_err());
+
+        assert!(parse(args("dump 2023")).is_err());
+        assert!(parse(args("dump 2023 5 --format json")).is_err());
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let options = parse(args("2023 5 --input -")).unwrap();
+        assert_eq!(options.input, InputSource::Stdin);
+
+        let options = parse(args("2023 5 --input example.txt")).unwrap();
+        assert_eq!(
+            options.input,
+            InputSource::File(PathBuf::from("example.txt"))
+        );
+
+        let options = parse(args("all --input-dir /tmp/aoc")).unwrap();
+        assert_eq!(options.input, InputSource::Root(PathBuf::from("/tmp/aoc")));
+
+        assert!(parse(args("2023 --input example.txt")).is_err());
+        assert!(parse(args("2023 5 --input a.txt --input-dir /tmp")).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use std::fs;
+
+use crate::{
+    answers::Answers,
+    aoc_error::AocError,
+    bench,
+    cli::{Command, Format, Options, Record, Selection},
+    output,
+    read_file::{read_file, InputSource},
+    registry, run_all, scaffold,
+    solver::{DumpOptions, Puzzle},
+    verify,
+};
+
+/// Run the command selected on the command line.
+pub fn run(options: &Options) -> Result<(), AocError> {
+    if let (Command::New, Selection::Day(year, day)) = (&options.command, &options.selection) {
+        return new_day(*year, *day, options);
+    }
+
+    let puzzles = select(&options.selection)?;
+
+    match (&options.command, &options.selection) {
+        (Command::Solve, Selection::Day(year, day)) => solve_day(puzzles[0], *year, *day, options),
+        (Command::Solve, _) => solve_all(puzzles, options),
+        (Command::Bench { runs }, _) => bench(puzzles, *runs, options),
+        (Command::Verify { record }, _) => verify(puzzles, *record, options),
+        (Command::Dump(dump_options), Selection::Day(year, day)) => {
+            dump(puzzles[0], *year, *day, *dump_options, options)
+        }
+        (Command::Dot, Selection::Day(year, day)) => dot(puzzles[0], *year, *day, options),
+        (Command::Dump(_) | Command::Dot, _) => {
+            unreachable!("dump and dot are only parsed with a single day")
+        }
+        (Command::New, _) => unreachable!("new is handled before selecting puzzles"),
+    }
+}
+
+fn select(selection: &Selection) -> Result<Vec<&'static dyn Puzzle>, AocError> {
+    match *selection {
+        Selection::Day(year, day) => registry::find(year, day)
+            .map(|puzzle| vec![puzzle])
+            .ok_or_else(|| {
+                if registry::solvers().any(|p| p.year() == year) {
+                    AocError::UnsupportedDay(year, day)
+                } else {
+                    AocError::UnsupportedYear(year)
+                }
+            }),
+        Selection::Year(year) => {
+            let puzzles: Vec<_> = registry::solvers().filter(|p| p.year() == year).collect();
+            if puzzles.is_empty() {
+                return Err(AocError::UnsupportedYear(year));
+            }
+            Ok(puzzles)
+        }
+        Selection::All => Ok(registry::solvers().collect()),
+    }
+}
+
+fn solve_day(puzzle: &dyn Puzzle, year: u16, day: u8, options: &Options) -> Result<(), AocError> {
+    let input = read_file(&options.input, year, day)?;
+    let (result, timings) = puzzle
+        .solve_timed(&input, options.parts)
+        .map_err(|error| error.in_file(options.input.path(year, day)))?;
+
+    match options.format {
+        Format::Text => println!("Starting {year}-{day}\n{result}"),
+        format => output::print(
+            format,
+            &output::solved_records(year, day, &result, &timings),
+        ),
+    }
+    Ok(())
+}
+
+fn solve_all(puzzles: Vec<&dyn Puzzle>, options: &Options) -> Result<(), AocError> {
+    let rows = run_all::run(puzzles.into_iter(), options.parts, &options.input);
+    match options.format {
+        Format::Text => run_all::print_table(&rows),
+        format => {
+            // the records only carry the status, the errors themselves go to the standard error
+            for row in &rows {
+                if let run_all::Outcome::Failed(error) = row.outcome() {
+                    eprintln!("{}-{} failed: {error}", row.year(), row.day());
+                }
+            }
+            output::print(format, &run_all::records(&rows));
+        }
+    }
+
+    match rows.iter().filter(|row| row.failed()).count() {
+        0 => Ok(()),
+        failed => Err(AocError::solver(format!("{failed} day(s) failed"))),
+    }
+}
+
+fn dump(
+    puzzle: &dyn Puzzle,
+    year: u16,
+    day: u8,
+    dump_options: DumpOptions,
+    options: &Options,
+) -> Result<(), AocError> {
+    print_view(year, day, options, "dump", |input| {
+        puzzle.dump(input, dump_options)
+    })
+}
+
+fn dot(puzzle: &dyn Puzzle, year: u16, day: u8, options: &Options) -> Result<(), AocError> {
+    print_view(year, day, options, "graph", |input| {
+        Ok(puzzle.dot(input)?.map(|dot| dot.to_string()))
+    })
+}
+
+/// Print what `view` makes of the input of the day, which may have nothing to show.
+fn print_view(
+    year: u16,
+    day: u8,
+    options: &Options,
+    what: &str,
+    view: impl FnOnce(&str) -> Result<Option<String>, AocError>,
+) -> Result<(), AocError> {
+    let input = read_file(&options.input, year, day)?;
+    let view = view(&input).map_err(|error| error.in_file(options.input.path(year, day)))?;
+
+    match view {
+        Some(view) => {
+            println!("{view}");
+            Ok(())
+        }
+        None => Err(AocError::solver(format!(
+            "{year}-{day} has no {what} to print"
+        ))),
+    }
+}
+
+/// Benchmark every selected day, skipping the ones without input when several are selected.
+/// A failing day is reported without stopping the other benchmarks.
+fn bench(puzzles: Vec<&dyn Puzzle>, runs: u32, options: &Options) -> Result<(), AocError> {
+    let single = puzzles.len() == 1;
+    let mut benches = Vec::new();
+    let mut failed = 0;
+
+    for puzzle in puzzles {
+        let (year, day) = (puzzle.year(), puzzle.day());
+        let bench = read_file(&options.input, year, day).and_then(|input| {
+            puzzle
+                .bench(&input, options.parts, runs)
+                .map_err(|error| error.in_file(options.input.path(year, day)))
+        });
+
+        match bench {
+            Ok(bench) => benches.push(bench),
+            Err(error) if single => return Err(error),
+            Err(AocError::MissingInput(path)) => {
+                eprintln!("Skipping {year}-{day}: missing {}", path.display());
+            }
+            Err(error) => {
+                eprintln!("{year}-{day} failed: {error}");
+                failed += 1;
+            }
+        }
+    }
+
+    match options.format {
+        Format::Text => bench::print_table(&benches),
+        format => output::print(format, &bench::records(&benches)),
+    }
+
+    match failed {
+        0 => Ok(()),
+        failed => Err(AocError::solver(format!("{failed} day(s) failed"))),
+    }
+}
+
+/// Check every selected day against the answers store, failing on any mismatch or error.
+fn verify(
+    puzzles: Vec<&dyn Puzzle>,
+    record: Option<Record>,
+    options: &Options,
+) -> Result<(), AocError> {
+    let mut answers = Answers::load(&options.answers)?;
+    let rows = run_all::run(puzzles.into_iter(), options.parts, &options.input);
+    let verified = verify::verify(&rows, &mut answers, record);
+    verify::print_table(&verified);
+
+    if record.is_some() {
+        answers.save()?;
+    }
+
+    match verified.iter().map(verify::Verified::regressions).sum() {
+        0 => Ok(()),
+        regressions => Err(AocError::solver(format!(
+            "{regressions} part(s) failed verification"
+        ))),
+    }
+}
+
+fn new_day(year: u16, day: u8, options: &Options) -> Result<(), AocError> {
+    if registry::find(year, day).is_some() {
+        return Err(AocError::scaffold(format!(
+            "{year}-{day} is already registered"
+        )));
+    }
+
+    for file in scaffold::new_day(&scaffold::source_root()?, year, day)? {
+        println!("Wrote {}", file.display());
+    }
+
+    if let InputSource::Root(_) = options.input {
+        let input = options.input.path(year, day);
+        if let Some(dir) = input.parent() {
+            fs::create_dir_all(dir).map_err(|error| AocError::Io(dir.to_owned(), error))?;
+        }
+        println!("Save the puzzle input to {}", input.display());
+    }
+    Ok(())
+}
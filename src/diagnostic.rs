@@ -0,0 +1,176 @@
+use std::{fmt, path::PathBuf};
+
+use nom::error::{VerboseError, VerboseErrorKind};
+
+use crate::aoc_error::AocError;
+
+/// Result of a day's nom parser, keeping the grammar rules being parsed when it failed.
+pub type ParseResult<'a, T> = nom::IResult<&'a str, T, VerboseError<&'a str>>;
+
+/// Where and why an input did not match a day's grammar.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    file: Option<PathBuf>,
+    line: usize,
+    column: usize,
+    source_line: String,
+    expected: Vec<&'static str>,
+    message: String,
+}
+
+impl Diagnostic {
+    /// Diagnostic located at `at`, which must be a sub-slice of `input`.
+    pub fn new(input: &str, at: &str, message: impl Into<String>) -> Self {
+        let offset = (at.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|offset| *offset <= input.len())
+            .unwrap_or(0);
+        let consumed = &input[..offset];
+        let line_start = consumed.rfind('\n').map_or(0, |index| index + 1);
+        let source_line = input[line_start..].lines().next().unwrap_or_default();
+
+        Self {
+            file: None,
+            line: consumed.matches('\n').count() + 1,
+            column: consumed[line_start..].chars().count() + 1,
+            source_line: source_line.trim_end_matches('\r').to_owned(),
+            expected: Vec::new(),
+            message: message.into(),
+        }
+    }
+
+    pub fn set_file(&mut self, file: PathBuf) {
+        self.file = Some(file);
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Parse error")?;
+        if let Some(file) = &self.file {
+            write!(f, " in {}", file.display())?;
+        }
+        write!(f, " at line {}, column {}: ", self.line, self.column)?;
+
+        let mut rules = self.expected.iter();
+        if let Some(rule) = rules.next() {
+            write!(f, "expected {rule}")?;
+            for rule in rules {
+                write!(f, " in {rule}")?;
+            }
+            write!(f, ", ")?;
+        }
+        writeln!(f, "{}", self.message)?;
+
+        let number = self.line.to_string();
+        let margin = " ".repeat(number.len());
+        writeln!(f, "{margin} |")?;
+        writeln!(f, "{number} | {}", self.source_line)?;
+        write!(f, "{margin} | {}^", " ".repeat(self.column - 1))
+    }
+}
+
+/// Convert a nom failure on `input` into an [`AocError::Parse`].
+pub fn report(input: &str, error: nom::Err<VerboseError<&str>>) -> AocError {
+    let errors = match error {
+        nom::Err::Incomplete(_) => {
+            return AocError::Parse(Diagnostic::new(input, &input[input.len()..], "incomplete"))
+        }
+        nom::Err::Error(error) | nom::Err::Failure(error) => error.errors,
+    };
+
+    let Some((at, kind)) = errors.first() else {
+        return AocError::Parse(Diagnostic::new(input, input, "unknown error"));
+    };
+
+    let found = found(at);
+    let message = match kind {
+        VerboseErrorKind::Char(c) => format!("{found} instead of {c:?}"),
+        VerboseErrorKind::Context(_) | VerboseErrorKind::Nom(_) => found,
+    };
+
+    let mut diagnostic = Diagnostic::new(input, at, message);
+    diagnostic.expected = errors
+        .iter()
+        .filter_map(|(_, kind)| match kind {
+            VerboseErrorKind::Context(context) => Some(*context),
+            _ => None,
+        })
+        .collect();
+
+    AocError::Parse(diagnostic)
+}
+
+fn found(at: &str) -> String {
+    match at.chars().next() {
+        None => "found end of input".to_owned(),
+        Some('\r') => "found a carriage return (CRLF line ending?)".to_owned(),
+        Some(c) => format!("found {c:?}"),
+    }
+}
+
+/// Run `parser` over the whole `input`, reporting failures with [`report`].
+/// Only trailing whitespace may be left unparsed.
+pub fn parse_with<'a, T>(
+    input: &'a str,
+    parser: impl FnOnce(&'a str) -> ParseResult<'a, T>,
+) -> Result<T, AocError> {
+    let (rest, parsed) = parser(input).map_err(|error| report(input, error))?;
+    if rest.trim().is_empty() {
+        return Ok(parsed);
+    }
+
+    let mut diagnostic = Diagnostic::new(input, rest, found(rest));
+    diagnostic.expected.push("end of input");
+    Err(AocError::Parse(diagnostic))
+}
+
+#[cfg(test)]
+mod test {
+    use nom::{character::complete::u8 as nom_u8, error::context, sequence::preceded};
+
+    use super::{parse_with, Diagnostic, ParseResult};
+    use crate::aoc_error::AocError;
+
+    #[test]
+    fn test_position() {
+        const INPUT: &str = "abc\ndef\nghi";
+        let diagnostic = Diagnostic::new(INPUT, &INPUT[5..], "oops");
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 2));
+        assert_eq!(diagnostic.source_line, "def");
+
+        let diagnostic = Diagnostic::new(INPUT, &INPUT[..0], "oops");
+        assert_eq!((diagnostic.line, diagnostic.column), (1, 1));
+    }
+
+    #[test]
+    fn test_trailing_input() {
+        let Err(AocError::Parse(diagnostic)) = parse_with("12\n13\n", nom_u8) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((diagnostic.line, diagnostic.column), (1, 3));
+        assert_eq!(diagnostic.expected.first().copied(), Some("end of input"));
+
+        assert_eq!(parse_with("12\n", nom_u8).unwrap(), 12);
+    }
+
+    #[test]
+    fn test_report() {
+        fn number(input: &str) -> ParseResult<'_, u8> {
+            context(
+                "number",
+                preceded(nom::character::complete::newline, nom_u8),
+            )(input)
+        }
+
+        let Err(AocError::Parse(diagnostic)) = parse_with("\r\n12", number) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(diagnostic.expected.first().copied(), Some("number"));
+        assert_eq!(
+            diagnostic.to_string(),
+            "Parse error at line 1, column 1: expected number, \
+             found a carriage return (CRLF line ending?) instead of '\\n'\n  |\n1 | \n  | ^"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Graphviz DOT text, to render the structure of an input offline.
+
+use std::fmt;
+
+/// Graph written one statement per line, every identifier quoted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dot {
+    name: String,
+    directed: bool,
+    statements: Vec<String>,
+}
+
+impl Dot {
+    pub fn digraph(name: &str) -> Self {
+        Self::new(name, true)
+    }
+
+    /// Graph whose edges have no direction.
+    pub fn graph(name: &str) -> Self {
+        Self::new(name, false)
+    }
+
+    fn new(name: &str, directed: bool) -> Self {
+        Self {
+            name: name.to_owned(),
+            directed,
+            statements: Vec::new(),
+        }
+    }
+
+    pub fn node(&mut self, id: &str, attributes: &[(&str, &str)]) {
+        let statement = format!("{}{}", quote(id), list(attributes));
+        self.statements.push(statement);
+    }
+
+    pub fn edge(&mut self, from: &str, to: &str, attributes: &[(&str, &str)]) {
+        let arrow = if self.directed { "->" } else { "--" };
+        let statement = format!("{} {arrow} {}{}", quote(from), quote(to), list(attributes));
+        self.statements.push(statement);
+    }
+}
+
+impl fmt::Display for Dot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.directed { "digraph" } else { "graph" };
+        writeln!(f, "{kind} {} {{", quote(&self.name))?;
+        for statement in &self.statements {
+            writeln!(f, "  {statement};")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// ` [key="value", ...]`, or nothing without attributes.
+fn list(attributes: &[(&str, &str)]) -> String {
+    if attributes.is_empty() {
+        return String::new();
+    }
+    let attributes: Vec<_> = attributes
+        .iter()
+        .map(|(key, value)| format!("{key}={}", quote(value)))
+        .collect();
+    format!(" [{}]", attributes.join(", "))
+}
+
+#[cfg(test)]
+mod test {
+    use super::Dot;
+
+    #[test]
+    fn test_render() {
+        let mut dot = Dot::digraph("net");
+        dot.node("A", &[("color", "red")]);
+        dot.edge("A", "say \"B\"", &[("label", "L"), ("style", "bold")]);
+        dot.edge("A", "A", &[]);
+        assert_eq!(
+            dot.to_string(),
+            "digraph \"net\" {
+  \"A\" [color=\"red\"];
+  \"A\" -> \"say \\\"B\\\"\" [label=\"L\", style=\"bold\"];
+  \"A\" -> \"A\";
+}"
+        );
+
+        let mut dot = Dot::graph("loop");
+        dot.edge("1", "2", &[]);
+        assert_eq!(dot.to_string(), "graph \"loop\" {\n  \"1\" -- \"2\";\n}");
+    }
+}
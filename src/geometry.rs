@@ -0,0 +1,164 @@
+use std::ops::{Add, Neg};
+
+/// Position on a grid, `y` going down. Coordinates are as wide as the grid indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub const fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    /// `self` moved by `offset`, unless it would go below zero.
+    pub const fn offset(self, offset: Offset) -> Option<Self> {
+        match (
+            self.x.checked_add_signed(offset.dx),
+            self.y.checked_add_signed(offset.dy),
+        ) {
+            (Some(x), Some(y)) => Some(Self { x, y }),
+            _ => None,
+        }
+    }
+
+    /// The neighbour of `self` in `direction`.
+    pub const fn step(self, direction: Direction) -> Option<Self> {
+        self.offset(direction.offset())
+    }
+
+    /// Offset leading from `self` to `other`.
+    pub fn offset_to(self, other: Self) -> Offset {
+        let signed = |n: usize| isize::try_from(n).expect("coordinate to fit in isize");
+        Offset::new(
+            signed(other.x) - signed(self.x),
+            signed(other.y) - signed(self.y),
+        )
+    }
+
+    pub const fn manhattan_distance(self, other: Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((x, y): (usize, usize)) -> Self {
+        Self { x, y }
+    }
+}
+
+/// Signed displacement between two points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Offset {
+    pub dx: isize,
+    pub dy: isize,
+}
+
+impl Offset {
+    pub const fn new(dx: isize, dy: isize) -> Self {
+        Self { dx, dy }
+    }
+
+    pub const fn manhattan_length(self) -> usize {
+        self.dx.unsigned_abs() + self.dy.unsigned_abs()
+    }
+}
+
+impl Add for Offset {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.dx + other.dx, self.dy + other.dy)
+    }
+}
+
+impl Neg for Offset {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.dx, -self.dy)
+    }
+}
+
+/// One of the 4 orthogonal directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise, starting up.
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub const fn offset(self) -> Offset {
+        match self {
+            Self::Up => Offset::new(0, -1),
+            Self::Right => Offset::new(1, 0),
+            Self::Down => Offset::new(0, 1),
+            Self::Left => Offset::new(-1, 0),
+        }
+    }
+
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Right => Self::Left,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+        }
+    }
+
+    #[must_use]
+    pub const fn turn_right(self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+
+    #[must_use]
+    pub const fn turn_left(self) -> Self {
+        self.turn_right().opposite()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Direction, Offset, Point};
+
+    #[test]
+    fn test_point() {
+        let point = Point::new(300, 1000);
+        assert_eq!(point.step(Direction::Up), Some(Point::new(300, 999)));
+        assert_eq!(Point::new(0, 5).step(Direction::Left), None);
+        assert_eq!(
+            point.offset(Offset::new(-300, 24)),
+            Some(Point::new(0, 1024))
+        );
+
+        let other = Point::new(1, 70_000);
+        assert_eq!(point.manhattan_distance(other), 299 + 69_000);
+        assert_eq!(point.offset_to(other).manhattan_length(), 299 + 69_000);
+        assert_eq!(point.offset(point.offset_to(other)), Some(other));
+    }
+
+    #[test]
+    fn test_direction() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.opposite().offset(), -direction.offset());
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(
+                direction.offset() + direction.opposite().offset(),
+                Offset::default()
+            );
+        }
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+    }
+}
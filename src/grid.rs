@@ -0,0 +1,394 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    aoc_error::AocError,
+    geometry::{Direction, Offset, Point},
+};
+
+/// Offsets of the 8 surrounding neighbours, diagonals included.
+const NEIGHBORS_8: [Offset; 8] = [
+    Offset::new(-1, -1),
+    Offset::new(0, -1),
+    Offset::new(1, -1),
+    Offset::new(1, 0),
+    Offset::new(1, 1),
+    Offset::new(0, 1),
+    Offset::new(-1, 1),
+    Offset::new(-1, 0),
+];
+
+/// Rectangular 2D grid stored row by row, indexed by [`Point`] with `y` going down.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Grid where each cell is built from its position, 0×0 when either side is 0.
+    pub fn from_fn(width: usize, height: usize, cell: impl FnMut(Point) -> T) -> Self {
+        let (width, height) = if width == 0 || height == 0 {
+            (0, 0)
+        } else {
+            (width, height)
+        };
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::new(x, y)))
+            .map(cell)
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parse one cell per character, every line being a row of the same width.
+    pub fn parse<E: Display>(
+        input: &str,
+        cell: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Self, AocError> {
+        Self::parse_within(input, input, cell)
+    }
+
+    /// Parse several grids separated by blank lines.
+    pub fn parse_many<E: Display>(
+        input: &str,
+        mut cell: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Vec<Self>, AocError> {
+        let mut grids = Vec::new();
+        let mut block: Option<(usize, usize)> = None;
+
+        for line in input.split_inclusive('\n') {
+            let start = offset(input, line);
+            if line.trim().is_empty() {
+                if let Some((from, to)) = block.take() {
+                    grids.push(Self::parse_within(input, &input[from..to], &mut cell)?);
+                }
+            } else {
+                let from = block.map_or(start, |(from, _)| from);
+                block = Some((from, start + line.len()));
+            }
+        }
+        if let Some((from, to)) = block {
+            grids.push(Self::parse_within(input, &input[from..to], &mut cell)?);
+        }
+
+        if grids.is_empty() {
+            return Err(AocError::parse(input, input, "expected at least one grid"));
+        }
+        Ok(grids)
+    }
+
+    /// Parse `text`, a sub-slice of `source` used to locate errors.
+    fn parse_within<E: Display>(
+        source: &str,
+        text: &str,
+        mut cell: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Self, AocError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for line in text.trim_end_matches(['\n', '\r']).lines() {
+            let row_start = cells.len();
+            for (index, c) in line.char_indices() {
+                let at = &line[index..index + c.len_utf8()];
+                cells
+                    .push(cell(c).map_err(|error| AocError::parse(source, at, error.to_string()))?);
+            }
+
+            let row_width = cells.len() - row_start;
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    return Err(AocError::parse(
+                        source,
+                        line,
+                        format!("expected a row of {width} cells, found {row_width}"),
+                    ))
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Self {
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(AocError::parse(source, text, "expected a non-empty grid")),
+        }
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    pub const fn contains(&self, point: Point) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|index| &mut self.cells[index])
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        self.contains(point).then(|| point.y * self.width + point.x)
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // an empty grid has no cells to split, whatever the chunk size
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> Option<impl Iterator<Item = &T>> {
+        (x < self.width).then(|| self.cells.iter().skip(x).step_by(self.width))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.cells.iter().skip(x).step_by(self.width))
+    }
+
+    /// Every cell along with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| (Point::new(index % self.width, index / self.width), cell))
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(&mut f).collect(),
+        }
+    }
+
+    /// Orthogonal neighbours of `point` within the grid.
+    pub fn neighbors4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbors(point, Direction::ALL.map(Direction::offset))
+    }
+
+    /// Orthogonal and diagonal neighbours of `point` within the grid.
+    pub fn neighbors8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbors(point, NEIGHBORS_8)
+    }
+
+    fn neighbors<const N: usize>(
+        &self,
+        point: Point,
+        offsets: [Offset; N],
+    ) -> impl Iterator<Item = Point> + '_ {
+        offsets
+            .into_iter()
+            .filter_map(move |offset| point.offset(offset))
+            .filter(|neighbor| self.contains(*neighbor))
+    }
+
+    /// Render back to text, one line per row.
+    pub fn render(&self, mut cell: impl FnMut(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&mut cell).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Swap rows and columns.
+    #[must_use]
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.height, self.width, |p| {
+            self[Point::new(p.y, p.x)].clone()
+        })
+    }
+
+    #[must_use]
+    pub fn rotate_clockwise(&self) -> Self {
+        let height = self.height;
+        Self::from_fn(height, self.width, |p| {
+            self[Point::new(p.y, height - 1 - p.x)].clone()
+        })
+    }
+
+    #[must_use]
+    pub fn rotate_counterclockwise(&self) -> Self {
+        let width = self.width;
+        Self::from_fn(self.height, width, |p| {
+            self[Point::new(width - 1 - p.y, p.x)].clone()
+        })
+    }
+
+    /// Mirror left to right.
+    #[must_use]
+    pub fn flip_horizontal(&self) -> Self {
+        let width = self.width;
+        Self::from_fn(width, self.height, |p| {
+            self[Point::new(width - 1 - p.x, p.y)].clone()
+        })
+    }
+
+    /// Mirror top to bottom.
+    #[must_use]
+    pub fn flip_vertical(&self) -> Self {
+        let height = self.height;
+        Self::from_fn(self.width, height, |p| {
+            self[Point::new(p.x, height - 1 - p.y)].clone()
+        })
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point:?} is out of a {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point:?} is out of a {width}x{height} grid"))
+    }
+}
+
+fn offset(input: &str, part: &str) -> usize {
+    part.as_ptr() as usize - input.as_ptr() as usize
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::Infallible;
+
+    use super::Grid;
+    use crate::geometry::Point;
+
+    const INPUT: &str = "ab\ncd\nef";
+
+    fn grid() -> Grid<char> {
+        Grid::parse(INPUT, Ok::<_, Infallible>).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid[Point::new(1, 2)], 'f');
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+        assert_eq!(grid.render(|c| *c), INPUT);
+
+        let error = Grid::parse("ab\nc", Ok::<_, Infallible>).unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+
+        let error = Grid::parse("..\n.x", |c| match c {
+            '.' => Ok(()),
+            _ => Err("expected '.'"),
+        })
+        .unwrap_err();
+        assert!(error.to_string().contains("line 2, column 2"));
+    }
+
+    #[test]
+    fn test_parse_many() {
+        let grids = Grid::parse_many("ab\ncd\n\nxyz\n", Ok::<_, Infallible>).unwrap();
+        assert_eq!(grids.len(), 2);
+        assert_eq!(grids[1].render(|c| *c), "xyz");
+
+        let error = Grid::parse_many("ab\n\nx\ny!\n", |c| match c {
+            '!' => Err("unexpected '!'"),
+            c => Ok(c),
+        })
+        .unwrap_err();
+        assert!(error.to_string().contains("line 4, column 2"));
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = grid();
+        let rows: Vec<_> = grid.rows().collect();
+        assert_eq!(rows, [['a', 'b'], ['c', 'd'], ['e', 'f']]);
+
+        let columns: Vec<String> = grid.columns().map(|column| column.collect()).collect();
+        assert_eq!(columns, ["ace", "bdf"]);
+        assert_eq!(grid.column(1).unwrap().collect::<String>(), "bdf");
+        assert!(grid.column(2).is_none());
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = grid();
+        assert_eq!(grid.transpose().render(|c| *c), "ace\nbdf");
+        assert_eq!(grid.rotate_clockwise().render(|c| *c), "eca\nfdb");
+        assert_eq!(grid.rotate_counterclockwise().render(|c| *c), "bdf\nace");
+        assert_eq!(grid.flip_horizontal().render(|c| *c), "ba\ndc\nfe");
+        assert_eq!(grid.flip_vertical().render(|c| *c), "ef\ncd\nab");
+        assert_eq!(
+            grid.rotate_clockwise().rotate_counterclockwise(),
+            grid.clone()
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = grid();
+        assert_eq!(
+            grid.neighbors4(Point::new(0, 0)).collect::<Vec<_>>(),
+            [Point::new(1, 0), Point::new(0, 1)]
+        );
+        assert_eq!(grid.neighbors8(Point::new(0, 0)).count(), 3);
+        assert_eq!(grid.neighbors8(Point::new(1, 1)).count(), 5);
+        assert_eq!(grid.neighbors4(Point::new(0, 1)).count(), 3);
+    }
+
+    #[test]
+    fn test_empty_grid() {
+        for (width, height) in [(0, 3), (3, 0), (0, 0)] {
+            let grid = Grid::from_fn(width, height, |_| 0);
+            assert_eq!((grid.width(), grid.height()), (0, 0));
+            assert_eq!(grid.rows().count(), 0);
+            assert_eq!(grid.iter().count(), 0);
+            assert_eq!(grid.columns().count(), 0);
+            assert_eq!(grid.transpose(), grid);
+            assert_eq!(grid.get(Point::new(0, 0)), None);
+        }
+    }
+
+    #[test]
+    fn test_large_grid() {
+        let grid = Grid::from_fn(300, 400, |p| p.x * 1000 + p.y);
+        let corner = Point::new(299, 399);
+        assert_eq!(grid[corner], 299_399);
+        assert_eq!(grid.get(Point::new(300, 0)), None);
+        assert_eq!(grid.neighbors8(corner).count(), 3);
+        assert_eq!(grid.neighbors4(Point::new(256, 256)).count(), 4);
+        assert_eq!(grid.iter().last(), Some((corner, &299_399)));
+
+        let rotated = grid.rotate_clockwise();
+        assert_eq!((rotated.width(), rotated.height()), (400, 300));
+        assert_eq!(rotated[Point::new(0, 299)], 299_399);
+        assert_eq!(grid.transpose()[Point::new(399, 299)], 299_399);
+    }
+}
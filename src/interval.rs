@@ -0,0 +1,345 @@
+use std::fmt;
+
+use num::PrimInt;
+
+/// Half-open range of integers `[start, end)`, empty when `end <= start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T> Interval<T> {
+    pub const fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+}
+
+impl<T: PrimInt> Interval<T> {
+    /// From `first` to `last` included, `None` when `last` is the largest value of `T`.
+    pub fn from_inclusive(first: T, last: T) -> Option<Self> {
+        Some(Self::new(first, last.checked_add(&T::one())?))
+    }
+
+    /// `length` values from `start`, `None` when the end does not fit in `T`.
+    pub fn with_length(start: T, length: T) -> Option<Self> {
+        Some(Self::new(start, start.checked_add(&length)?))
+    }
+
+    pub const fn start(&self) -> T {
+        self.start
+    }
+
+    pub const fn end(&self) -> T {
+        self.end
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::zero()
+        } else {
+            self.end - self.start
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Whether every value of `other` is in `self`, which holds for an empty `other`.
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    /// Whether at least one value is in both.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// Values below `at`, and values from `at` on.
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let at = at.clamp(self.start, self.end.max(self.start));
+        (Self::new(self.start, at), Self::new(at, self.end))
+    }
+
+    /// Every value moved by `offset`, `None` when a bound does not fit in `T`.
+    pub fn shift(&self, offset: T) -> Option<Self> {
+        Some(Self::new(
+            self.start.checked_add(&offset)?,
+            self.end.checked_add(&offset)?,
+        ))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {})", self.start, self.end)
+    }
+}
+
+/// Set of integers stored as sorted, disjoint and non-adjacent intervals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub const fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Sort and merge `intervals`, dropping the empty ones.
+    fn normalized(mut intervals: Vec<Interval<T>>) -> Self {
+        intervals.retain(|interval| !interval.is_empty());
+        intervals.sort_unstable();
+
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+        Self { intervals: merged }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The disjoint intervals, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    /// Smallest value of the set.
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(Interval::start)
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval);
+        *self = Self::normalized(intervals);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self
+            .intervals
+            .partition_point(|interval| interval.end <= value);
+        self.intervals
+            .get(index)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn contains_interval(&self, other: &Interval<T>) -> bool {
+        other.is_empty()
+            || self
+                .intervals
+                .iter()
+                .any(|interval| interval.contains_interval(other))
+    }
+
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.intervals
+            .iter()
+            .any(|interval| interval.overlaps(other))
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalized(self.iter().chain(other.iter()).copied().collect())
+    }
+
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let (mut left, mut right) = (self.iter().peekable(), other.iter().peekable());
+        while let (Some(a), Some(b)) = (left.peek().copied(), right.peek().copied()) {
+            result.push(a.intersection(b));
+            // the interval ending first cannot meet anything further in the other set
+            if a.end <= b.end {
+                left.next();
+            } else {
+                right.next();
+            }
+        }
+        Self::normalized(result)
+    }
+
+    /// Values of `self` that are not in `other`.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        for interval in self.iter() {
+            let mut rest = *interval;
+            for cut in other.iter().filter(|cut| cut.overlaps(interval)) {
+                result.push(Interval::new(rest.start, cut.start));
+                rest.start = cut.end;
+            }
+            result.push(rest);
+        }
+        Self::normalized(result)
+    }
+
+    /// Values below `at`, and values from `at` on.
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let (below, above) = self.iter().map(|interval| interval.split_at(at)).unzip();
+        (Self::normalized(below), Self::normalized(above))
+    }
+
+    /// Every value moved by `offset`, `None` when a bound does not fit in `T`.
+    pub fn shift(&self, offset: T) -> Option<Self> {
+        let intervals = self
+            .iter()
+            .map(|interval| interval.shift(offset))
+            .collect::<Option<_>>()?;
+        Some(Self { intervals })
+    }
+}
+
+impl<T: PrimInt> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        Self::normalized(vec![interval])
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(intervals: I) -> Self {
+        Self::normalized(intervals.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::{Interval, IntervalSet};
+
+    const VALUES: i64 = 64;
+
+    /// Xorshift, enough to generate reproducible cases.
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self, below: i64) -> i64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % below.unsigned_abs()) as i64
+        }
+
+        fn interval(&mut self) -> Interval<i64> {
+            let start = self.next(VALUES);
+            Interval::new(start, start + self.next(12) - 2)
+        }
+
+        fn set(&mut self) -> IntervalSet<i64> {
+            let count = self.next(5);
+            (0..count).map(|_| self.interval()).collect()
+        }
+    }
+
+    fn naive(set: &IntervalSet<i64>) -> BTreeSet<i64> {
+        set.iter()
+            .flat_map(|interval| interval.start()..interval.end())
+            .collect()
+    }
+
+    fn is_normalized(set: &IntervalSet<i64>) -> bool {
+        set.iter().all(|interval| !interval.is_empty())
+            && set
+                .intervals
+                .windows(2)
+                .all(|pair| pair[0].end() < pair[1].start())
+    }
+
+    #[test]
+    fn test_interval() {
+        let interval = Interval::from_inclusive(2, 4).unwrap();
+        assert_eq!(interval, Interval::new(2, 5));
+        assert_eq!(interval.len(), 3);
+        assert!(interval.contains(4) && !interval.contains(5));
+        assert!(interval.contains_interval(&Interval::new(3, 5)));
+        assert!(!interval.overlaps(&Interval::new(5, 8)));
+        assert_eq!(
+            interval.split_at(3),
+            (Interval::new(2, 3), Interval::new(3, 5))
+        );
+        assert_eq!(interval.shift(-2), Some(Interval::new(0, 3)));
+        assert_eq!(interval.to_string(), "[2, 5)");
+
+        assert_eq!(Interval::from_inclusive(0, u64::MAX), None);
+        assert_eq!(Interval::with_length(i64::MAX - 1, 2), None);
+        assert_eq!(Interval::new(0, i64::MAX).shift(1), None);
+    }
+
+    #[test]
+    fn test_set_against_naive() {
+        let mut random = Random(0x2545_f491_4f6c_dd1d);
+        for _ in 0..2_000 {
+            let (left, right) = (random.set(), random.set());
+            let (naive_left, naive_right) = (naive(&left), naive(&right));
+
+            let union = left.union(&right);
+            let intersection = left.intersection(&right);
+            let difference = left.difference(&right);
+            for set in [&left, &union, &intersection, &difference] {
+                assert!(is_normalized(set), "{set:?}");
+            }
+            assert_eq!(naive(&union), &naive_left | &naive_right);
+            assert_eq!(naive(&intersection), &naive_left & &naive_right);
+            assert_eq!(naive(&difference), &naive_left - &naive_right);
+
+            let value = random.next(VALUES + 10) - 5;
+            assert_eq!(left.contains(value), naive_left.contains(&value));
+            let (below, above) = left.split_at(value);
+            assert!(naive(&below).iter().all(|v| *v < value));
+            assert!(naive(&above).iter().all(|v| *v >= value));
+            assert_eq!(&naive(&below) | &naive(&above), naive_left);
+
+            let interval = random.interval();
+            let naive_interval: BTreeSet<_> = (interval.start()..interval.end()).collect();
+            assert_eq!(
+                left.overlaps(&interval),
+                !naive_interval.is_disjoint(&naive_left)
+            );
+            assert_eq!(
+                left.contains_interval(&interval),
+                naive_interval.is_subset(&naive_left)
+            );
+
+            let offset = random.next(20) - 10;
+            let shifted: BTreeSet<_> = naive_left.iter().map(|v| v + offset).collect();
+            assert_eq!(naive(&left.shift(offset).unwrap()), shifted);
+        }
+    }
+
+    #[test]
+    fn test_interval_against_naive() {
+        let mut random = Random(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..2_000 {
+            let (a, b) = (random.interval(), random.interval());
+            let naive_a: BTreeSet<_> = (a.start()..a.end()).collect();
+            let naive_b: BTreeSet<_> = (b.start()..b.end()).collect();
+
+            assert_eq!(a.overlaps(&b), !naive_a.is_disjoint(&naive_b));
+            assert_eq!(a.contains_interval(&b), naive_b.is_subset(&naive_a));
+            let intersection = a.intersection(&b);
+            assert_eq!(
+                (intersection.start()..intersection.end()).collect::<BTreeSet<_>>(),
+                &naive_a & &naive_b
+            );
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Advent of Code solutions, one module per year and one solver per day.
+//!
+//! Every day implements [`solver::Solver`] and is listed in [`registry`], so a day can be
+//! parsed and solved without going through the binary:
+//!
+//! ```
+//! use advent_of_rust::{registry, solver::Parts};
+//!
+//! let day6 = registry::find(2023, 6).unwrap();
+//! let result = day6.solve("Time: 7\nDistance: 9", Parts::Silver).unwrap();
+//! assert_eq!(result.silver().to_string(), "4");
+//! ```
+
+pub mod advent_of_code_2022;
+pub mod advent_of_code_2023;
+mod answers;
+pub mod aoc_error;
+pub mod aoc_result;
+pub mod bench;
+pub mod checked;
+pub mod cli;
+pub mod commands;
+pub mod diagnostic;
+pub mod dot;
+pub mod geometry;
+pub mod grid;
+pub mod interval;
+mod output;
+pub mod read_file;
+pub mod registry;
+mod run_all;
+mod scaffold;
+pub mod solver;
+#[cfg(test)]
+mod stress;
+mod table;
+mod verify;
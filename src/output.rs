@@ -0,0 +1,208 @@
+use std::{fmt::Write, time::Duration};
+
+use crate::{
+    aoc_result::{AOCResult, Part, PartResult, Timings},
+    cli::Format,
+};
+
+/// Value of a single field of a machine-readable record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Number(u128),
+    Text(String),
+    Null,
+}
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_owned())
+    }
+}
+
+impl From<Duration> for Value {
+    fn from(duration: Duration) -> Self {
+        Self::Number(duration.as_nanos())
+    }
+}
+
+/// Named fields of one output line, in column order.
+pub type Record = Vec<(&'static str, Value)>;
+
+/// The fields shared by every command: year, day, part, answer, duration and status.
+pub fn part_record(
+    year: u16,
+    day: u8,
+    part: &str,
+    result: Option<&PartResult>,
+    duration: Option<Duration>,
+    status: &str,
+) -> Record {
+    let answer = match result {
+        Some(PartResult::Solved(answer)) => Value::Text(answer.to_string()),
+        _ => Value::Null,
+    };
+
+    vec![
+        ("year", Value::Number(year.into())),
+        ("day", Value::Number(day.into())),
+        ("part", part.into()),
+        ("answer", answer),
+        ("duration_ns", duration.map_or(Value::Null, Value::from)),
+        ("status", status.into()),
+    ]
+}
+
+/// Records of both parts of a solved day.
+pub fn solved_records(year: u16, day: u8, result: &AOCResult, timings: &Timings) -> Vec<Record> {
+    Part::ALL
+        .into_iter()
+        .map(|part| {
+            let result = result.part(part);
+            let duration = matches!(result, PartResult::Solved(_)).then(|| timings.part(part));
+            part_record(
+                year,
+                day,
+                part.name(),
+                Some(result),
+                duration,
+                result.status(),
+            )
+        })
+        .collect()
+}
+
+/// Print `records` in a machine-readable `format`, the columns of the first record being the header.
+/// Text output is left to the table of each command.
+pub fn print(format: Format, records: &[Record]) {
+    print!("{}", render(format, records));
+}
+
+fn render(format: Format, records: &[Record]) -> String {
+    let mut out = String::new();
+    let separator = match format {
+        Format::Text => return out,
+        Format::Json => {
+            for record in records {
+                let fields: Vec<_> = record
+                    .iter()
+                    .map(|(name, value)| format!("\"{name}\":{}", json(value)))
+                    .collect();
+                writeln!(out, "{{{}}}", fields.join(",")).unwrap();
+            }
+            return out;
+        }
+        Format::Csv => ",",
+        Format::Tsv => "\t",
+    };
+
+    let Some(first) = records.first() else {
+        return out;
+    };
+    let header: Vec<_> = first.iter().map(|(name, _)| *name).collect();
+    writeln!(out, "{}", header.join(separator)).unwrap();
+
+    for record in records {
+        let cells: Vec<_> = record
+            .iter()
+            .map(|(_, value)| match format {
+                Format::Csv => csv(value),
+                _ => tsv(value),
+            })
+            .collect();
+        writeln!(out, "{}", cells.join(separator)).unwrap();
+    }
+    out
+}
+
+fn json(value: &Value) -> String {
+    match value {
+        Value::Number(n) => n.to_string(),
+        Value::Null => "null".to_owned(),
+        Value::Text(text) => {
+            let mut escaped = String::from('"');
+            for c in text.chars() {
+                match c {
+                    '"' => escaped.push_str("\\\""),
+                    '\\' => escaped.push_str("\\\\"),
+                    '\n' => escaped.push_str("\\n"),
+                    '\t' => escaped.push_str("\\t"),
+                    c if c.is_control() => write!(escaped, "\\u{:04x}", u32::from(c)).unwrap(),
+                    c => escaped.push(c),
+                }
+            }
+            escaped.push('"');
+            escaped
+        }
+    }
+}
+
+fn csv(value: &Value) -> String {
+    match value {
+        Value::Number(n) => n.to_string(),
+        Value::Null => String::new(),
+        Value::Text(text) if text.contains([',', '"', '\n', '\r']) => {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        }
+        Value::Text(text) => text.clone(),
+    }
+}
+
+/// TSV has no quoting, so tabs and line breaks are replaced by spaces.
+fn tsv(value: &Value) -> String {
+    match value {
+        Value::Number(n) => n.to_string(),
+        Value::Null => String::new(),
+        Value::Text(text) => text.replace(['\t', '\n', '\r'], " "),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{part_record, render, Value};
+    use crate::{
+        aoc_result::{Answer, PartResult},
+        cli::Format,
+    };
+
+    #[test]
+    fn test_render() {
+        let solved = PartResult::Solved(Answer::Text("a,\"b\"".to_owned()));
+        let records = [
+            part_record(
+                2023,
+                5,
+                "silver",
+                Some(&solved),
+                Some(Duration::from_micros(3)),
+                "solved",
+            ),
+            part_record(2023, 5, "gold", None, None, "failed"),
+        ];
+
+        assert_eq!(
+            render(Format::Json, &records),
+            r#"{"year":2023,"day":5,"part":"silver","answer":"a,\"b\"","duration_ns":3000,"status":"solved"}
+{"year":2023,"day":5,"part":"gold","answer":null,"duration_ns":null,"status":"failed"}
+"#
+        );
+        assert_eq!(
+            render(Format::Csv, &records),
+            "year,day,part,answer,duration_ns,status
+2023,5,silver,\"a,\"\"b\"\"\",3000,solved
+2023,5,gold,,,failed
+"
+        );
+        assert_eq!(
+            render(Format::Tsv, &records[1..]),
+            "year\tday\tpart\tanswer\tduration_ns\tstatus\n2023\t5\tgold\t\t\tfailed\n"
+        );
+        assert_eq!(render(Format::Csv, &[]), "");
+    }
+
+    #[test]
+    fn test_json_escape() {
+        assert_eq!(super::json(&Value::from("a\\\n\u{1}")), r#""a\\\n\u0001""#);
+    }
+}
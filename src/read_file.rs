@@ -1,3 +1,95 @@
-pub fn read_file(year: u16, day: u8) -> Result<String, std::io::Error> {
-    std::fs::read_to_string(format!("./input/{year}/{day:02}.txt"))
+use std::{
+    env,
+    io::{self, ErrorKind, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::aoc_error::AocError;
+
+/// Environment variable overriding the directory holding `{year}/{day:02}.txt` inputs.
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+
+/// Where puzzle inputs are read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// Directory holding one `{year}/{day:02}.txt` file per day.
+    Root(PathBuf),
+    /// A single explicit input file.
+    File(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    /// Input root from [`INPUT_DIR_ENV`], or the closest `input` directory from the current one.
+    pub fn from_env() -> Self {
+        if let Some(root) = env::var_os(INPUT_DIR_ENV) {
+            return Self::Root(root.into());
+        }
+
+        let closest = env::current_dir().ok().and_then(|current| {
+            current
+                .ancestors()
+                .map(|dir| dir.join("input"))
+                .find(|dir| dir.is_dir())
+        });
+        Self::Root(closest.unwrap_or_else(|| PathBuf::from("./input")))
+    }
+
+    /// Path used to name the input in diagnostics.
+    pub fn path(&self, year: u16, day: u8) -> PathBuf {
+        match self {
+            Self::Root(root) => root.join(year.to_string()).join(format!("{day:02}.txt")),
+            Self::File(path) => path.clone(),
+            Self::Stdin => PathBuf::from("<stdin>"),
+        }
+    }
+}
+
+pub fn read_file(source: &InputSource, year: u16, day: u8) -> Result<String, AocError> {
+    let path = source.path(year, day);
+    let content = match source {
+        InputSource::Stdin => read_stdin(),
+        InputSource::Root(_) | InputSource::File(_) => std::fs::read_to_string(&path),
+    };
+
+    match content {
+        Ok(content) => Ok(content),
+        Err(error) if error.kind() == ErrorKind::NotFound => Err(AocError::MissingInput(path)),
+        Err(error) => Err(AocError::Io(path, error)),
+    }
+}
+
+fn read_stdin() -> io::Result<String> {
+    let mut content = String::new();
+    io::stdin().read_to_string(&mut content)?;
+    Ok(content)
+}
+
+impl From<&Path> for InputSource {
+    /// `-` stands for the standard input, anything else for an explicit file.
+    fn from(path: &Path) -> Self {
+        if path == Path::new("-") {
+            Self::Stdin
+        } else {
+            Self::File(path.to_owned())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use super::InputSource;
+
+    #[test]
+    fn test_path() {
+        let root = InputSource::Root(PathBuf::from("/tmp/aoc"));
+        assert_eq!(root.path(2023, 5), Path::new("/tmp/aoc/2023/05.txt"));
+
+        let file = InputSource::from(Path::new("example.txt"));
+        assert_eq!(file.path(2023, 5), Path::new("example.txt"));
+
+        assert_eq!(InputSource::from(Path::new("-")), InputSource::Stdin);
+    }
 }
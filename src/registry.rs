@@ -0,0 +1,11 @@
+use crate::{advent_of_code_2022, advent_of_code_2023, solver::Puzzle};
+
+const YEARS: &[&[&dyn Puzzle]] = &[advent_of_code_2022::SOLVERS, advent_of_code_2023::SOLVERS];
+
+pub fn solvers() -> impl Iterator<Item = &'static dyn Puzzle> {
+    YEARS.iter().flat_map(|year| year.iter().copied())
+}
+
+pub fn find(year: u16, day: u8) -> Option<&'static dyn Puzzle> {
+    solvers().find(|puzzle| puzzle.year() == year && puzzle.day() == day)
+}
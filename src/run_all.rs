@@ -0,0 +1,181 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    aoc_error::AocError,
+    aoc_result::{AOCResult, Part, Timings},
+    output::{self, Record},
+    read_file::{read_file, InputSource},
+    solver::{Parts, Puzzle},
+    table,
+};
+
+pub enum Outcome {
+    Solved(AOCResult, Timings),
+    Skipped,
+    Failed(AocError),
+}
+
+pub struct Row {
+    year: u16,
+    day: u8,
+    outcome: Outcome,
+    elapsed: Duration,
+}
+
+impl Row {
+    pub const fn new(year: u16, day: u8, outcome: Outcome, elapsed: Duration) -> Self {
+        Self {
+            year,
+            day,
+            outcome,
+            elapsed,
+        }
+    }
+
+    pub const fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub const fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub const fn outcome(&self) -> &Outcome {
+        &self.outcome
+    }
+
+    pub const fn failed(&self) -> bool {
+        matches!(self.outcome, Outcome::Failed(_))
+    }
+
+    fn cells(&self) -> [String; 5] {
+        let (silver, gold) = match &self.outcome {
+            Outcome::Solved(result, _) => (result.silver().to_string(), result.gold().to_string()),
+            Outcome::Skipped => ("skipped".to_owned(), "skipped".to_owned()),
+            Outcome::Failed(error) => {
+                // multi-line diagnostics would break the table, keep their summary only
+                let error = error.to_string();
+                let summary = error.lines().next().unwrap_or_default();
+                (format!("error: {summary}"), String::new())
+            }
+        };
+
+        [
+            self.year.to_string(),
+            self.day.to_string(),
+            silver,
+            gold,
+            format!("{:?}", self.elapsed),
+        ]
+    }
+}
+
+/// Solve every given puzzle, one after another.
+/// A failing day is reported in its row instead of stopping the whole run.
+pub fn run<'a>(
+    puzzles: impl Iterator<Item = &'a dyn Puzzle>,
+    parts: Parts,
+    source: &InputSource,
+) -> Vec<Row> {
+    puzzles
+        .map(|puzzle| run_one(puzzle, parts, source))
+        .collect()
+}
+
+fn run_one(puzzle: &dyn Puzzle, parts: Parts, source: &InputSource) -> Row {
+    let (year, day) = (puzzle.year(), puzzle.day());
+    let start = Instant::now();
+
+    let outcome = match read_file(source, year, day) {
+        Ok(input) => {
+            // days report their failures as errors, this only keeps a bug in one of them from
+            // ending the whole run
+            match panic::catch_unwind(AssertUnwindSafe(|| puzzle.solve_timed(&input, parts))) {
+                Ok(Ok((result, timings))) => Outcome::Solved(result, timings),
+                Ok(Err(error)) => Outcome::Failed(error.in_file(source.path(year, day))),
+                Err(payload) => Outcome::Failed(AocError::solver(panic_message(payload.as_ref()))),
+            }
+        }
+        Err(AocError::MissingInput(_)) => Outcome::Skipped,
+        Err(error) => Outcome::Failed(error),
+    };
+
+    Row::new(year, day, outcome, start.elapsed())
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| (*message).to_owned())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "solver panicked".to_owned())
+}
+
+pub fn print_table(rows: &[Row]) {
+    const HEADER: [&str; 5] = ["year", "day", "silver", "gold", "elapsed"];
+
+    let cells: Vec<_> = rows.iter().map(|row| row.cells().to_vec()).collect();
+    table::print(&HEADER, &cells);
+}
+
+/// One record per part.
+pub fn records(rows: &[Row]) -> Vec<Record> {
+    rows.iter()
+        .flat_map(|row| match &row.outcome {
+            Outcome::Solved(result, timings) => {
+                output::solved_records(row.year, row.day, result, timings)
+            }
+            outcome => {
+                let status = if let Outcome::Failed(_) = outcome {
+                    "failed"
+                } else {
+                    "missing_input"
+                };
+                Part::ALL
+                    .map(|part| {
+                        output::part_record(row.year, row.day, part.name(), None, None, status)
+                    })
+                    .to_vec()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use super::{run, Outcome};
+    use crate::{aoc_result::AOCResult, read_file::InputSource, registry, solver::Parts};
+
+    #[test]
+    fn test_run() {
+        let root = env::temp_dir().join(format!("run-all-{}", std::process::id()));
+        fs::create_dir_all(root.join("2023")).unwrap();
+        fs::write(root.join("2023/07.txt"), "22 5\n").unwrap();
+        fs::write(
+            root.join("2023/09.txt"),
+            "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45\n",
+        )
+        .unwrap();
+
+        let puzzles = registry::solvers()
+            .filter(|puzzle| puzzle.year() == 2023 && (7..=9).contains(&puzzle.day()));
+        let rows = run(puzzles, Parts::Both, &InputSource::Root(root.clone()));
+        fs::remove_dir_all(&root).unwrap();
+
+        let days: Vec<_> = rows.iter().map(|row| row.day()).collect();
+        assert_eq!(days, [7, 8, 9]);
+        assert!(matches!(rows[0].outcome(), Outcome::Failed(_)));
+        assert!(rows[0].failed());
+        assert!(matches!(rows[1].outcome(), Outcome::Skipped));
+        let Outcome::Solved(result, _) = rows[2].outcome() else {
+            panic!("expected day 9 to be solved");
+        };
+        assert_eq!(*result, AOCResult::from((114_i64, 2_i64)));
+    }
+}
@@ -0,0 +1,447 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::aoc_error::AocError;
+
+const SOLVERS_OPEN: &str = "pub const SOLVERS: &[&dyn Puzzle] = &[";
+const YEARS_OPEN: &str = "const YEARS: &[&[&dyn Puzzle]] = &[";
+const USE_OPEN: &str = "use crate::{";
+/// rustfmt's default widths for a line and for the items of an array on a single line.
+const MAX_WIDTH: usize = 100;
+const ARRAY_WIDTH: usize = 60;
+
+/// Generate the module of a new day and register it, creating its year module when needed.
+/// Returns the files that were written.
+pub fn new_day(root: &Path, year: u16, day: u8) -> Result<Vec<PathBuf>, AocError> {
+    if !(1..=25).contains(&day) {
+        return Err(AocError::scaffold(format!(
+            "{day} is not an Advent of Code day"
+        )));
+    }
+
+    let src = root.join("src");
+    let year_module = format!("advent_of_code_{year}");
+    let year_file = src.join(format!("{year_module}.rs"));
+    let day_file = src.join(&year_module).join(format!("day{day}.rs"));
+    if day_file.exists() {
+        return Err(AocError::scaffold(format!(
+            "{} already exists, refusing to overwrite it",
+            day_file.display()
+        )));
+    }
+
+    let mut written = Vec::new();
+    if year_file.exists() {
+        edit(&year_file, |source| register_day(source, day))?;
+    } else {
+        write(&year_file, &year_template(day))?;
+
+        let crate_root = [src.join("lib.rs"), src.join("main.rs")]
+            .into_iter()
+            .find(|file| file.exists())
+            .ok_or_else(|| AocError::scaffold("no crate root found in src/"))?;
+        edit(&crate_root, |source| register_year_module(source, year))?;
+        let registry = src.join("registry.rs");
+        edit(&registry, |source| register_year(source, year))?;
+        written.extend([crate_root, registry]);
+    }
+    written.push(year_file);
+
+    fs::create_dir_all(day_file.parent().unwrap_or(&src))
+        .map_err(|error| AocError::Io(day_file.clone(), error))?;
+    write(&day_file, &day_template(year, day))?;
+    written.push(day_file);
+
+    Ok(written)
+}
+
+/// Closest directory from the current one holding the sources of this crate.
+pub fn source_root() -> Result<PathBuf, AocError> {
+    let current = env::current_dir().map_err(|error| AocError::Io(PathBuf::from("."), error))?;
+    current
+        .ancestors()
+        .find(|dir| dir.join("Cargo.toml").is_file() && dir.join("src/registry.rs").is_file())
+        .map(Path::to_path_buf)
+        .ok_or_else(|| AocError::scaffold("new must be run from within the repository"))
+}
+
+fn write(path: &Path, content: &str) -> Result<(), AocError> {
+    fs::write(path, content).map_err(|error| AocError::Io(path.to_owned(), error))
+}
+
+fn edit(
+    path: &Path,
+    change: impl FnOnce(&str) -> Result<String, AocError>,
+) -> Result<(), AocError> {
+    let source = fs::read_to_string(path).map_err(|error| AocError::Io(path.to_owned(), error))?;
+    write(path, &change(&source)?)
+}
+
+fn register_day(source: &str, day: u8) -> Result<String, AocError> {
+    let source = insert_mod(source, "day", &format!("day{day}"))?;
+    insert_item(
+        &source,
+        SOLVERS_OPEN,
+        &format!("&day{day}::Day{day}"),
+        number,
+    )
+}
+
+fn register_year_module(source: &str, year: u16) -> Result<String, AocError> {
+    insert_mod(source, "advent_of_code_", &format!("advent_of_code_{year}"))
+}
+
+fn register_year(source: &str, year: u16) -> Result<String, AocError> {
+    let source = insert_item(
+        source,
+        USE_OPEN,
+        &format!("advent_of_code_{year}"),
+        |item| item.to_owned(),
+    )?;
+    insert_item(
+        &source,
+        YEARS_OPEN,
+        &format!("advent_of_code_{year}::SOLVERS"),
+        number,
+    )
+}
+
+/// First number in `item`, so that `day10` sorts after `day9`.
+fn number(item: &str) -> u32 {
+    item.split(|c: char| !c.is_ascii_digit())
+        .find(|digits| !digits.is_empty())
+        .and_then(|digits| digits.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Add `mod {name};` next to the other modules starting with `prefix`, with the same visibility.
+fn insert_mod(source: &str, prefix: &str, name: &str) -> Result<String, AocError> {
+    let is_mod = |line: &str| {
+        let line = line.strip_prefix("pub ").unwrap_or(line);
+        line.strip_prefix("mod ")
+            .is_some_and(|module| module.starts_with(prefix))
+    };
+
+    let mut lines: Vec<_> = source.lines().map(str::to_owned).collect();
+    let first = lines
+        .iter()
+        .position(|line| is_mod(line))
+        .ok_or_else(|| AocError::scaffold(format!("no `mod {prefix}...;` declaration found")))?;
+    let last = first
+        + lines[first..]
+            .iter()
+            .take_while(|line| is_mod(line))
+            .count();
+
+    let visibility = if lines[first].starts_with("pub ") {
+        "pub "
+    } else {
+        ""
+    };
+    lines.insert(last, format!("{visibility}mod {name};"));
+    lines[first..=last].sort_by_key(|line| {
+        let line = line.strip_prefix("pub ").unwrap_or(line);
+        line.trim_start_matches("mod ")
+            .trim_end_matches(';')
+            .to_owned()
+    });
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Add `item` to the comma separated list opened by `open`, keeping it sorted by `key`.
+fn insert_item<K: Ord>(
+    source: &str,
+    open: &str,
+    item: &str,
+    key: impl Fn(&str) -> K,
+) -> Result<String, AocError> {
+    let start = source
+        .find(open)
+        .ok_or_else(|| AocError::scaffold(format!("`{open}` not found")))?
+        + open.len();
+    let close = if open.ends_with('{') { '}' } else { ']' };
+    let end = start
+        + source[start..]
+            .find(close)
+            .ok_or_else(|| AocError::scaffold(format!("`{open}` is not closed")))?;
+
+    let mut items: Vec<_> = source[start..end]
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect();
+    items.push(item);
+    items.sort_by_key(|item| key(item));
+    items.dedup();
+
+    // laid out the way rustfmt would, so the sources stay formatted
+    let (before, after) = (&source[..start], &source[end..]);
+    let inline = items.join(", ");
+    let line = before.rsplit('\n').next().unwrap_or_default().len()
+        + inline.len()
+        + after.split('\n').next().unwrap_or_default().len();
+    let list = if line <= MAX_WIDTH && (close == '}' || inline.len() <= ARRAY_WIDTH) {
+        inline
+    } else if close == '}' {
+        packed(&items)
+    } else {
+        items
+            .iter()
+            .map(|item| format!("\n    {item},"))
+            .collect::<String>()
+            + "\n"
+    };
+    Ok(format!("{before}{list}{after}"))
+}
+
+/// `items` filling indented lines, the way rustfmt lays out the imports of a long `use`.
+fn packed(items: &[&str]) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for item in items {
+        match lines.last_mut() {
+            // a space before the item and a comma after it
+            Some(line) if line.len() + item.len() + 2 <= MAX_WIDTH => {
+                *line += &format!(" {item},");
+            }
+            _ => lines.push(format!("    {item},")),
+        }
+    }
+    lines
+        .iter()
+        .map(|line| format!("\n{line}"))
+        .collect::<String>()
+        + "\n"
+}
+
+fn year_template(day: u8) -> String {
+    format!(
+        "use crate::solver::Puzzle;
+
+pub mod day{day};
+
+{SOLVERS_OPEN}&day{day}::Day{day}];
+"
+    )
+}
+
+fn day_template(year: u16, day: u8) -> String {
+    format!(
+        r#"use crate::{{
+    aoc_error::AocError,
+    solver::{{NotImplemented, PartOutput, Solver}},
+}};
+
+pub struct Day{day};
+
+impl Solver for Day{day} {{
+    const YEAR: u16 = {year};
+    const DAY: u8 = {day};
+
+    type Input = Vec<Line>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {{
+        parse::parse(input)
+    }}
+
+    fn silver(input: &Self::Input) -> impl PartOutput {{
+        silver(input)
+    }}
+
+    fn gold(input: &Self::Input) -> impl PartOutput {{
+        gold(input)
+    }}
+}}
+
+pub type Line = String;
+
+fn silver(_input: &[Line]) -> NotImplemented {{
+    NotImplemented
+}}
+
+fn gold(_input: &[Line]) -> NotImplemented {{
+    NotImplemented
+}}
+
+mod parse {{
+    use nom::{{
+        character::complete::{{newline, not_line_ending}},
+        combinator::map,
+        error::context,
+        multi::separated_list1,
+    }};
+
+    use super::Line;
+    use crate::{{
+        aoc_error::AocError,
+        diagnostic::{{self, ParseResult}},
+    }};
+
+    pub fn parse(input: &str) -> Result<Vec<Line>, AocError> {{
+        diagnostic::parse_with(input, separated_list1(newline, line))
+    }}
+
+    fn line(input: &str) -> ParseResult<'_, Line> {{
+        context("line", map(not_line_ending, str::to_owned))(input)
+    }}
+}}
+
+#[cfg(test)]
+mod test {{
+    use super::parse::parse;
+
+    const INPUT: &str = "";
+
+    #[test]
+    fn test_parse() {{
+        let parsed = parse(INPUT).unwrap();
+
+        assert_eq!(parsed.len(), 1);
+    }}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use super::{new_day, register_day, register_year, register_year_module};
+
+    #[test]
+    fn test_new_day() {
+        let root = env::temp_dir().join(format!("scaffold-{}", std::process::id()));
+        let src = root.join("src");
+        fs::create_dir_all(&src).unwrap();
+        for file in ["lib.rs", "registry.rs"] {
+            let source = fs::read_to_string(format!("{}/src/{file}", env!("CARGO_MANIFEST_DIR")));
+            fs::write(src.join(file), source.unwrap()).unwrap();
+        }
+
+        let written = new_day(&root, 2019, 3).unwrap();
+        let year = src.join("advent_of_code_2019.rs");
+        let day = src.join("advent_of_code_2019/day3.rs");
+        assert_eq!(
+            written,
+            [
+                src.join("lib.rs"),
+                src.join("registry.rs"),
+                year.clone(),
+                day.clone()
+            ]
+        );
+        let read = |path: &std::path::Path| fs::read_to_string(path).unwrap();
+        assert!(read(&src.join("lib.rs")).contains("pub mod advent_of_code_2019;\n"));
+        assert!(read(&src.join("registry.rs")).contains("advent_of_code_2019::SOLVERS"));
+        assert!(read(&year).contains("&[&day3::Day3]"));
+        assert!(read(&day).contains("const DAY: u8 = 3;"));
+
+        // the day is kept as it is, even when edited since
+        fs::write(&day, "// edited").unwrap();
+        assert!(new_day(&root, 2019, 3).is_err());
+        assert_eq!(read(&day), "// edited");
+
+        assert_eq!(
+            new_day(&root, 2019, 4).unwrap(),
+            [year.clone(), src.join("advent_of_code_2019/day4.rs")]
+        );
+        assert!(read(&year).contains("pub mod day3;\npub mod day4;\n"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_register_day() {
+        const YEAR: &str = "use crate::solver::Puzzle;
+
+pub mod day1;
+pub mod day9;
+
+pub const SOLVERS: &[&dyn Puzzle] = &[&day1::Day1, &day9::Day9];
+";
+        assert_eq!(
+            register_day(YEAR, 10).unwrap(),
+            "use crate::solver::Puzzle;
+
+pub mod day1;
+pub mod day10;
+pub mod day9;
+
+pub const SOLVERS: &[&dyn Puzzle] = &[&day1::Day1, &day9::Day9, &day10::Day10];
+"
+        );
+
+        // too wide for rustfmt to keep the array on one line
+        const WIDE: &str = "use crate::solver::Puzzle;
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+
+pub const SOLVERS: &[&dyn Puzzle] = &[&day1::Day1, &day2::Day2, &day3::Day3, &day4::Day4];
+";
+        assert!(register_day(WIDE, 5).unwrap().ends_with(
+            "pub const SOLVERS: &[&dyn Puzzle] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+];
+"
+        ));
+    }
+
+    #[test]
+    fn test_register_year() {
+        const ROOT: &str = "pub mod advent_of_code_2022;
+pub mod advent_of_code_2023;
+pub mod aoc_error;
+";
+        assert_eq!(
+            register_year_module(ROOT, 2015).unwrap(),
+            "pub mod advent_of_code_2015;
+pub mod advent_of_code_2022;
+pub mod advent_of_code_2023;
+pub mod aoc_error;
+"
+        );
+
+        const REGISTRY: &str = "use crate::{advent_of_code_2023, solver::Puzzle};
+
+const YEARS: &[&[&dyn Puzzle]] = &[advent_of_code_2023::SOLVERS];
+";
+        assert_eq!(
+            register_year(REGISTRY, 2024).unwrap(),
+            "use crate::{advent_of_code_2023, advent_of_code_2024, solver::Puzzle};
+
+const YEARS: &[&[&dyn Puzzle]] = &[advent_of_code_2023::SOLVERS, advent_of_code_2024::SOLVERS];
+"
+        );
+
+        let registry = [2024, 2025, 2026, 2027]
+            .into_iter()
+            .fold(REGISTRY.to_owned(), |registry, year| {
+                register_year(&registry, year).unwrap()
+            });
+        assert_eq!(
+            registry,
+            "use crate::{
+    advent_of_code_2023, advent_of_code_2024, advent_of_code_2025, advent_of_code_2026,
+    advent_of_code_2027, solver::Puzzle,
+};
+
+const YEARS: &[&[&dyn Puzzle]] = &[
+    advent_of_code_2023::SOLVERS,
+    advent_of_code_2024::SOLVERS,
+    advent_of_code_2025::SOLVERS,
+    advent_of_code_2026::SOLVERS,
+    advent_of_code_2027::SOLVERS,
+];
+"
+        );
+    }
+}
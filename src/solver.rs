@@ -0,0 +1,196 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    aoc_error::AocError,
+    aoc_result::{AOCResult, Answer, PartResult, Timings},
+    bench::{self, Bench, Phase},
+    dot::Dot,
+};
+
+/// A single Advent of Code puzzle: how to parse its input and how to solve both parts.
+pub trait Solver {
+    const YEAR: u16;
+    const DAY: u8;
+
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError>;
+
+    fn silver(_input: &Self::Input) -> impl PartOutput {
+        NotImplemented
+    }
+
+    fn gold(_input: &Self::Input) -> impl PartOutput {
+        NotImplemented
+    }
+
+    /// Human readable view of how the input gets solved, for the days that have one.
+    fn dump(_input: &Self::Input, _options: DumpOptions) -> Result<Option<String>, AocError> {
+        Ok(None)
+    }
+
+    /// Graphviz view of the structure of the input, for the days that have one.
+    fn dot(_input: &Self::Input) -> Result<Option<Dot>, AocError> {
+        Ok(None)
+    }
+}
+
+/// Which parts of a puzzle to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parts {
+    Silver,
+    Gold,
+    Both,
+}
+
+impl Parts {
+    pub const fn silver(self) -> bool {
+        matches!(self, Self::Silver | Self::Both)
+    }
+
+    pub const fn gold(self) -> bool {
+        matches!(self, Self::Gold | Self::Both)
+    }
+}
+
+/// How much a dump lists, for the days enumerating candidates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DumpOptions {
+    /// At most this many candidates for each item.
+    pub limit: usize,
+    /// Only how many candidates there are.
+    pub count_only: bool,
+}
+
+impl Default for DumpOptions {
+    fn default() -> Self {
+        Self {
+            limit: 20,
+            count_only: false,
+        }
+    }
+}
+
+/// Returned by a part that has not been solved yet.
+pub struct NotImplemented;
+
+/// What a part can return: either a plain answer, or a `Result` when solving it may fail.
+pub trait PartOutput {
+    fn into_output(self) -> Result<PartResult, AocError>;
+}
+
+macro_rules! impl_part_output {
+    ($($t:ty),*) => {
+        $(impl PartOutput for $t {
+            fn into_output(self) -> Result<PartResult, AocError> {
+                Ok(PartResult::Solved(Answer::from(self)))
+            }
+        })*
+    };
+}
+
+impl_part_output!(u32, u64, usize, i32, i64, String);
+
+impl PartOutput for NotImplemented {
+    fn into_output(self) -> Result<PartResult, AocError> {
+        Ok(PartResult::NotImplemented)
+    }
+}
+
+impl<T: PartOutput> PartOutput for Result<T, AocError> {
+    fn into_output(self) -> Result<PartResult, AocError> {
+        self.and_then(PartOutput::into_output)
+    }
+}
+
+/// Object safe view of a [`Solver`], so every day can be stored in the same registry.
+pub trait Puzzle {
+    fn year(&self) -> u16;
+    fn day(&self) -> u8;
+
+    /// Only the answers, without timings.
+    fn solve(&self, input: &str, parts: Parts) -> Result<AOCResult, AocError> {
+        self.solve_timed(input, parts).map(|(result, _)| result)
+    }
+
+    fn solve_timed(&self, input: &str, parts: Parts) -> Result<(AOCResult, Timings), AocError>;
+    fn dump(&self, input: &str, options: DumpOptions) -> Result<Option<String>, AocError>;
+    fn dot(&self, input: &str) -> Result<Option<Dot>, AocError>;
+    fn bench(&self, input: &str, parts: Parts, runs: u32) -> Result<Bench, AocError>;
+}
+
+impl<S: Solver> Puzzle for S {
+    fn year(&self) -> u16 {
+        S::YEAR
+    }
+
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn solve_timed(&self, input: &str, parts: Parts) -> Result<(AOCResult, Timings), AocError> {
+        let mut timings = Timings::default();
+        let (parsed, parse) = timed(|| S::parse(input));
+        timings.parse = parse;
+        let parsed = parsed?;
+
+        let silver = if parts.silver() {
+            let (silver, elapsed) = timed(|| S::silver(&parsed).into_output());
+            timings.silver = elapsed;
+            silver?
+        } else {
+            PartResult::Skipped
+        };
+        let gold = if parts.gold() {
+            let (gold, elapsed) = timed(|| S::gold(&parsed).into_output());
+            timings.gold = elapsed;
+            gold?
+        } else {
+            PartResult::Skipped
+        };
+
+        Ok((AOCResult::new(silver, gold), timings))
+    }
+
+    fn dump(&self, input: &str, options: DumpOptions) -> Result<Option<String>, AocError> {
+        S::dump(&S::parse(input)?, options)
+    }
+
+    fn dot(&self, input: &str) -> Result<Option<Dot>, AocError> {
+        S::dot(&S::parse(input)?)
+    }
+
+    fn bench(&self, input: &str, parts: Parts, runs: u32) -> Result<Bench, AocError> {
+        // every phase runs at least once
+        let runs = runs.max(1);
+        let (parsed, stats) = bench::measure(runs, || S::parse(input))?;
+        let mut phases = vec![(Phase::Parse, stats)];
+
+        let mut silver = PartResult::Skipped;
+        if parts.silver() {
+            let stats;
+            (silver, stats) = bench::measure(runs, || S::silver(&parsed).into_output())?;
+            phases.push((Phase::Silver, stats));
+        }
+        let mut gold = PartResult::Skipped;
+        if parts.gold() {
+            let stats;
+            (gold, stats) = bench::measure(runs, || S::gold(&parsed).into_output())?;
+            phases.push((Phase::Gold, stats));
+        }
+
+        Ok(Bench {
+            year: S::YEAR,
+            day: S::DAY,
+            runs,
+            phases,
+            result: AOCResult::new(silver, gold),
+        })
+    }
+}
+
+fn timed<T>(phase: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let value = phase();
+    (value, start.elapsed())
+}
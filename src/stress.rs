@@ -0,0 +1,341 @@
+//! Generated oversized inputs for every registered day: each must either give the exact answer
+//! or fail with [`AocError::Overflow`], never wrap or panic.
+
+use crate::{
+    aoc_error::AocError,
+    aoc_result::{AOCResult, Part, PartResult},
+    registry,
+    solver::{Parts, Puzzle},
+};
+
+type Stress = fn(&dyn Puzzle);
+
+const DAYS: &[(u16, u8, Stress)] = &[
+    (2022, 4, stress_2022_4),
+    (2023, 1, stress_2023_1),
+    (2023, 2, stress_2023_2),
+    (2023, 3, stress_2023_3),
+    (2023, 4, stress_2023_4),
+    (2023, 5, stress_2023_5),
+    (2023, 6, stress_2023_6),
+    (2023, 7, stress_2023_7),
+    (2023, 8, stress_2023_8),
+    (2023, 9, stress_2023_9),
+    (2023, 10, stress_2023_10),
+    (2023, 11, stress_2023_11),
+    (2023, 12, stress_2023_12),
+    (2023, 13, stress_2023_13),
+];
+
+fn solve(puzzle: &dyn Puzzle, input: &str) -> Result<AOCResult, AocError> {
+    puzzle.solve(input, Parts::Both)
+}
+
+fn lines(count: usize, line: impl FnMut(usize) -> String) -> String {
+    (0..count).map(line).collect::<Vec<_>>().join("\n")
+}
+
+fn assert_overflow(result: Result<AOCResult, AocError>) {
+    assert!(matches!(result, Err(AocError::Overflow(_))), "{result:?}");
+}
+
+fn stress_2022_4(puzzle: &dyn Puzzle) {
+    const PAIRS: usize = 20_000;
+    let input = lines(PAIRS, |index| {
+        let start = 5_000_000_000 + 10 * index as u64;
+        if index % 2 == 0 {
+            format!("{start}-{},{}-{}", start + 2, start + 1, start + 1)
+        } else {
+            format!("{start}-{start},{}-{}", start + 1, start + 1)
+        }
+    });
+    assert_eq!(
+        solve(puzzle, &input).unwrap(),
+        (PAIRS / 2, PAIRS / 2).into()
+    );
+}
+
+fn stress_2023_1(puzzle: &dyn Puzzle) {
+    const LINES: u64 = 20_000;
+    let input = lines(LINES as usize, |_| "nine1eight".to_owned());
+    assert_eq!(
+        solve(puzzle, &input).unwrap(),
+        (11 * LINES, 98 * LINES).into()
+    );
+}
+
+fn stress_2023_2(puzzle: &dyn Puzzle) {
+    const GAMES: u64 = 20_000;
+    let input = lines(GAMES as usize, |index| match index + 1 {
+        id if id % 2 == 1 => format!("Game {id}: 1 red, 2 green; 3 blue"),
+        id => format!("Game {id}: 300 red, 1 green, 1 blue"),
+    });
+    let odd_ids_sum = (GAMES / 2).pow(2);
+    assert_eq!(
+        solve(puzzle, &input).unwrap(),
+        (odd_ids_sum, GAMES / 2 * (6 + 300)).into()
+    );
+
+    let input = "Game 1: 4000000000 red, 4000000000 green, 4000000000 blue";
+    assert_overflow(solve(puzzle, input));
+}
+
+fn stress_2023_3(puzzle: &dyn Puzzle) {
+    const BLOCKS: u64 = 50;
+    const ROWS: u64 = 50;
+    let input = lines(2 * ROWS as usize, |index| {
+        let block = if index % 2 == 0 {
+            "12345*67890."
+        } else {
+            "............"
+        };
+        block.repeat(BLOCKS as usize)
+    });
+    let gears = BLOCKS * ROWS;
+    assert_eq!(
+        solve(puzzle, &input).unwrap(),
+        (gears * (12345 + 67890), gears * 12345 * 67890).into()
+    );
+
+    let error = solve(puzzle, "99999999999999999999*").unwrap_err();
+    assert!(
+        error.to_string().contains("part number too large"),
+        "{error}"
+    );
+}
+
+fn stress_2023_4(puzzle: &dyn Puzzle) {
+    const CARDS: u64 = 100_000;
+    let input = lines(CARDS as usize, |index| {
+        format!("Card {}: 1000 2000 | 1000 3000", index + 1)
+    });
+    // every card wins a copy of the next one, so card `n` ends up with `n` copies
+    assert_eq!(
+        solve(puzzle, &input).unwrap(),
+        (CARDS, CARDS * (CARDS + 1) / 2).into()
+    );
+
+    let numbers = (1..=70)
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    assert_overflow(solve(puzzle, &format!("Card 1: {numbers} | {numbers}")));
+}
+
+fn stress_2023_5(puzzle: &dyn Puzzle) {
+    const RANGES: u64 = 2_000;
+    const BASE: u64 = 1_000_000_000_000;
+    let seeds = (0..RANGES)
+        .map(|index| format!("{} 5", BASE + 10 * index))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let map = lines(RANGES as usize, |index| {
+        format!("{} {} 10", 100 + 20 * index, BASE + 10 * index as u64)
+    });
+    let input = format!("seeds: {seeds}\n\nseed-to-location map:\n{map}");
+    // the range lengths are read as seeds too by silver, and map to themselves
+    assert_eq!(solve(puzzle, &input).unwrap(), (5_i64, 100_i64).into());
+
+    let error = solve(
+        puzzle,
+        "seeds: 1 2\n\nseed-to-soil map:\n9223372036854775807 0 10",
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("map entry"), "{error}");
+}
+
+fn stress_2023_6(puzzle: &dyn Puzzle) {
+    // only holding for exactly half of the time beats 10^18 - 1, which an f64 cannot tell apart
+    let input = "Time: 2000000000 1000000000\nDistance: 999999999999999999 0";
+    let result = puzzle.solve(input, Parts::Silver).unwrap();
+    assert_eq!(result.silver().to_string(), (1_000_000_000 - 1).to_string());
+    // the concatenated time does not fit
+    assert_overflow(solve(puzzle, input));
+}
+
+fn stress_2023_7(puzzle: &dyn Puzzle) {
+    const HANDS: u64 = 30_000;
+    const BID: u64 = 1_000_000;
+    const CARDS: &[u8] = b"23456789TJQKA";
+    let input = lines(HANDS as usize, |index| {
+        let hand: String = (0..5)
+            .map(|position| CARDS[index / 13_usize.pow(position) % 13] as char)
+            .collect();
+        format!("{hand} {BID}")
+    });
+    let total = BID * HANDS * (HANDS + 1) / 2;
+    assert_eq!(solve(puzzle, &input).unwrap(), (total, total).into());
+}
+
+fn stress_2023_8(puzzle: &dyn Puzzle) {
+    /// Unique name never ending with 'A' or 'Z'.
+    fn node(index: usize) -> String {
+        let letter = |n: usize| char::from(b'A' + (n % 26) as u8);
+        format!(
+            "{}{}{}{}",
+            letter(index / 6760),
+            letter(index / 260),
+            letter(index / 10),
+            index % 10
+        )
+    }
+
+    /// Network where each `(start, end, length)` ghost reaches `end` every `length` steps.
+    fn network(ghosts: &[(&str, &str, usize)]) -> String {
+        let mut definitions = Vec::new();
+        let mut next_node = 0;
+        for &(start, end, length) in ghosts {
+            let nodes: Vec<_> = (next_node..next_node + length - 1).map(node).collect();
+            next_node += length - 1;
+
+            definitions.push(format!("{start} = ({0}, {0})", nodes[0]));
+            for pair in nodes.windows(2) {
+                definitions.push(format!("{} = ({1}, {1})", pair[0], pair[1]));
+            }
+            definitions.push(format!("{} = ({end}, {end})", nodes[length - 2]));
+            definitions.push(format!("{end} = ({0}, {0})", nodes[0]));
+        }
+        format!("LR\n\n{}", definitions.join("\n"))
+    }
+
+    const PRIMES: [usize; 5] = [10_007, 10_009, 10_037, 10_039, 10_061];
+    let mut ghosts = vec![
+        ("AAA", "ZZZ", PRIMES[0]),
+        ("B1A", "B1Z", PRIMES[1]),
+        ("B2A", "B2Z", PRIMES[2]),
+        ("B3A", "B3Z", PRIMES[3]),
+    ];
+    let product: u64 = PRIMES[..4].iter().map(|prime| *prime as u64).product();
+    assert_eq!(
+        solve(puzzle, &network(&ghosts)).unwrap(),
+        (PRIMES[0], product).into()
+    );
+
+    ghosts.push(("B4A", "B4Z", PRIMES[4]));
+    assert_overflow(solve(puzzle, &network(&ghosts)));
+}
+
+fn stress_2023_9(puzzle: &dyn Puzzle) {
+    const LINES: i64 = 10_000;
+    const STEP: i64 = 3_000_000_000;
+    let history = (0..20)
+        .map(|index| (index * STEP).to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let input = lines(LINES as usize, |_| history.clone());
+    assert_eq!(
+        solve(puzzle, &input).unwrap(),
+        (LINES * 20 * STEP, -LINES * STEP).into()
+    );
+
+    assert_overflow(solve(puzzle, "9223372036854775807 -9223372036854775808"));
+}
+
+fn stress_2023_10(puzzle: &dyn Puzzle) {
+    const SIZE: usize = 1_000;
+    let input = lines(SIZE, |y| match y {
+        0 => format!("S{}7", "-".repeat(SIZE - 2)),
+        y if y == SIZE - 1 => format!("L{}J", "-".repeat(SIZE - 2)),
+        _ => format!("|{}|", ".".repeat(SIZE - 2)),
+    });
+    assert_eq!(
+        solve(puzzle, &input).unwrap(),
+        (2 * (SIZE - 1), ((SIZE - 2) * (SIZE - 2)) as i64).into()
+    );
+}
+
+fn stress_2023_11(puzzle: &dyn Puzzle) {
+    const SIZE: usize = 1_000;
+    const GALAXIES: usize = 100;
+    // one galaxy every 10 rows and columns, on the diagonal
+    let input = lines(SIZE, |y| {
+        let mut row = ".".repeat(SIZE);
+        if y % 10 == 0 && y / 10 < GALAXIES {
+            row.replace_range(y..=y, "#");
+        }
+        row
+    });
+    // sum of the gaps between every pair, in steps of 10 on each axis
+    let gaps: usize = (1..GALAXIES).map(|gap| gap * (GALAXIES - gap)).sum();
+    assert_eq!(
+        solve(puzzle, &input).unwrap(),
+        (2 * gaps * (10 + 9), 2 * gaps * (10 + 9 * 999_999)).into()
+    );
+}
+
+fn stress_2023_12(puzzle: &dyn Puzzle) {
+    const LINES: usize = 10_000;
+    let mut input = lines(LINES, |_| "?###???????? 3,2,1".to_owned());
+    input.push_str(&format!("\n{} 300", "#".repeat(300)));
+    assert_eq!(
+        solve(puzzle, &input).unwrap(),
+        (10 * LINES as u64 + 1, 506_250 * LINES as u64 + 1).into()
+    );
+
+    // the C(181, 20) ways to spread twenty single damaged springs do not fit in a u64
+    let groups = vec!["1"; 20].join(",");
+    assert_overflow(solve(puzzle, &format!("{} {groups}", "?".repeat(200))));
+}
+
+fn stress_2023_13(puzzle: &dyn Puzzle) {
+    const PATTERNS: usize = 50;
+    const SIZE: usize = 300;
+    // a checkerboard mirrored around its middle row, so that no other line reflects
+    let checker = |y: usize| ["#.", ".#"][y % 2].repeat(SIZE / 2);
+    let pattern = lines(SIZE, |y| checker(y.min(SIZE - 1 - y)));
+    let input = vec![pattern.clone(); PATTERNS].join("\n\n");
+    let result = puzzle.solve(&input, Parts::Silver).unwrap();
+    assert_eq!(
+        result.silver().to_string(),
+        (PATTERNS * 100 * SIZE / 2).to_string()
+    );
+
+    let smudged = pattern.replacen('#', ".", 1);
+    let input = vec![smudged; PATTERNS].join("\n\n");
+    let result = puzzle.solve(&input, Parts::Gold).unwrap();
+    assert_eq!(
+        result.gold().to_string(),
+        (PATTERNS * 100 * SIZE / 2).to_string()
+    );
+}
+
+/// Whether a day is still as scaffolded, solving neither part of a one line input.
+fn is_stub(puzzle: &dyn Puzzle) -> bool {
+    puzzle.solve("stub", Parts::Both).is_ok_and(|result| {
+        Part::ALL
+            .into_iter()
+            .all(|part| *result.part(part) == PartResult::NotImplemented)
+    })
+}
+
+#[test]
+fn test_every_day() {
+    for puzzle in registry::solvers() {
+        let (year, day) = (puzzle.year(), puzzle.day());
+        match DAYS.iter().find(|(y, d, _)| (*y, *d) == (year, day)) {
+            Some((_, _, stress)) => stress(puzzle),
+            None if is_stub(puzzle) => {}
+            None => panic!("no stress input for {year}-{day}"),
+        }
+    }
+}
+
+#[test]
+fn test_is_stub() {
+    use crate::solver::Solver;
+
+    struct Stub;
+
+    impl Solver for Stub {
+        const YEAR: u16 = 2019;
+        const DAY: u8 = 3;
+
+        type Input = ();
+
+        fn parse(_input: &str) -> Result<Self::Input, AocError> {
+            Ok(())
+        }
+    }
+
+    assert!(is_stub(&Stub));
+}
@@ -0,0 +1,23 @@
+/// Print `rows` as a plain text table, each column as wide as its widest cell.
+pub fn print(header: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<_> = header.iter().map(|cell| cell.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    print_line(header.iter(), &widths);
+    print_line(widths.iter().map(|width| "-".repeat(*width)), &widths);
+    for row in rows {
+        print_line(row.iter(), &widths);
+    }
+}
+
+fn print_line(cells: impl Iterator<Item = impl AsRef<str>>, widths: &[usize]) {
+    let line: Vec<_> = cells
+        .zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell.as_ref()))
+        .collect();
+    println!("{}", line.join(" | ").trim_end());
+}
@@ -0,0 +1,171 @@
+use std::fmt;
+
+use crate::{
+    answers::{Answers, Expected},
+    aoc_result::{Answer, Part, PartResult},
+    cli::Record,
+    run_all::{Outcome, Row},
+    table,
+};
+
+/// Verification status of a single part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Mismatch(Answer),
+    /// The day could not be solved, with the summary of its error.
+    Fail(String),
+    /// Solved, but without any known answer to compare with.
+    Unrecorded(Answer),
+    /// Solved and recorded by this run.
+    Recorded(Answer),
+    /// Not selected, not implemented, or without input.
+    Skipped,
+}
+
+impl Status {
+    pub const fn is_regression(&self) -> bool {
+        matches!(self, Self::Mismatch(_) | Self::Fail(_))
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "pass"),
+            Self::Mismatch(answer) => write!(f, "MISMATCH (got {answer})"),
+            Self::Fail(error) => write!(f, "FAIL: {error}"),
+            Self::Unrecorded(answer) => write!(f, "unrecorded ({answer})"),
+            Self::Recorded(answer) => write!(f, "recorded ({answer})"),
+            Self::Skipped => write!(f, "-"),
+        }
+    }
+}
+
+pub struct Verified {
+    year: u16,
+    day: u8,
+    statuses: [Status; 2],
+}
+
+impl Verified {
+    pub fn regressions(&self) -> usize {
+        self.statuses.iter().filter(|s| s.is_regression()).count()
+    }
+}
+
+/// Compare every solved part with `answers`, recording the unknown ones when asked to.
+pub fn verify(rows: &[Row], answers: &mut Answers, record: Option<Record>) -> Vec<Verified> {
+    rows.iter()
+        .map(|row| {
+            let (year, day) = (row.year(), row.day());
+            let statuses = Part::ALL.map(|part| {
+                let answer = match row.outcome() {
+                    Outcome::Solved(result, _) => match result.part(part) {
+                        PartResult::Solved(answer) => answer,
+                        PartResult::Skipped | PartResult::NotImplemented => return Status::Skipped,
+                    },
+                    Outcome::Skipped => return Status::Skipped,
+                    Outcome::Failed(error) => {
+                        let error = error.to_string();
+                        return Status::Fail(error.lines().next().unwrap_or_default().to_owned());
+                    }
+                };
+
+                match (answers.get(year, day, part), record) {
+                    (Some(expected), _) if expected.matches(answer) => Status::Pass,
+                    (Some(_), _) => Status::Mismatch(answer.clone()),
+                    (None, None) => Status::Unrecorded(answer.clone()),
+                    (None, Some(record)) => {
+                        let expected = match record {
+                            Record::Plain => Expected::Plain(answer.to_string()),
+                            Record::Hashed => match Expected::hashed(answer) {
+                                Ok(expected) => expected,
+                                Err(error) => return Status::Fail(error.to_string()),
+                            },
+                        };
+                        answers.record(year, day, part, expected);
+                        Status::Recorded(answer.clone())
+                    }
+                }
+            });
+
+            Verified {
+                year,
+                day,
+                statuses,
+            }
+        })
+        .collect()
+}
+
+pub fn print_table(verified: &[Verified]) {
+    const HEADER: [&str; 4] = ["year", "day", "silver", "gold"];
+
+    let cells: Vec<_> = verified
+        .iter()
+        .map(|v| {
+            let [silver, gold] = &v.statuses;
+            vec![
+                v.year.to_string(),
+                v.day.to_string(),
+                silver.to_string(),
+                gold.to_string(),
+            ]
+        })
+        .collect();
+    table::print(&HEADER, &cells);
+}
+
+#[cfg(test)]
+mod test {
+    use super::{verify, Status};
+    use crate::{
+        answers::{Answers, Expected},
+        aoc_result::{AOCResult, Answer, Part, Timings},
+        cli::Record,
+        run_all::{Outcome, Row},
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn test_verify() {
+        let mut answers = Answers::default();
+        answers.record(2023, 5, Part::Silver, Expected::Plain("35".to_owned()));
+        answers.record(
+            2023,
+            5,
+            Part::Gold,
+            Expected::hashed(&Answer::Signed(45)).unwrap(),
+        );
+
+        let rows = [Row::new(
+            2023,
+            5,
+            Outcome::Solved((35_i64, 46_i64).into(), Timings::default()),
+            Duration::ZERO,
+        )];
+        let verified = verify(&rows, &mut answers, None);
+        assert_eq!(
+            verified[0].statuses,
+            [Status::Pass, Status::Mismatch(Answer::Signed(46))]
+        );
+        assert_eq!(verified[0].regressions(), 1);
+
+        let rows = [Row::new(
+            2023,
+            6,
+            Outcome::Solved(AOCResult::from((288_u32, 71503_u32)), Timings::default()),
+            Duration::ZERO,
+        )];
+        let verified = verify(&rows, &mut answers, Some(Record::Hashed));
+        assert_eq!(
+            verified[0].statuses[0],
+            Status::Recorded(Answer::Unsigned(288))
+        );
+        assert!(answers
+            .get(2023, 6, Part::Gold)
+            .unwrap()
+            .matches(&Answer::Unsigned(71503)));
+    }
+}